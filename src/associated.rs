@@ -1,7 +1,16 @@
-use syn::parse::{discouraged::Speculative as _, ParseStream};
+use proc_macro2::{Delimiter, Spacing, TokenTree};
+use syn::{
+    buffer::Cursor,
+    parse::{discouraged::Speculative as _, Parse as _, ParseStream},
+    punctuated::Punctuated,
+    Token,
+};
 
 use crate::util::SynErrorContext as _;
 
+/// Prefixes that force a specific kind of associated syntax item.
+const KINDS: &[&str] = &["expr", "pat", "type", "item"];
+
 /// Associated syntax item.
 /// Any [`DelegateEntry`] can have one.
 ///
@@ -14,6 +23,18 @@ use crate::util::SynErrorContext as _;
 /// Associated tokens are parsed in that exact order.
 /// If all parsing attempts fail, errors describing them are emitted.
 ///
+/// A kind prefix (`expr`, `pat`, `type` or `item`) forces a specific parser instead,
+/// e.g. `type HashMap<K, V>`. [Items](syn::Item) are only available with a prefix.
+///
+/// A braced list of [named items](NamedItem) (`{ name = <item>, ... }`) takes precedence
+/// over the above. Each named item is classified the same way.
+///
+/// A parenthesized list (`(<item>, ...)`) is split into positional items, each classified the same
+/// way. If any of them fails to parse, the list is treated as a single item instead.
+///
+/// Items that contain placeholders may not be valid syntax until they are substituted.
+/// If all parsing attempts fail for such an item, it is kept [verbatim](Self::Verbatim).
+///
 /// This classification is technically "wrong" for many inputs, but the output tokens are identical.
 /// In the end, this doesn't matter for raw substitution.
 ///
@@ -26,6 +47,19 @@ pub enum Associated {
     Expr(syn::Expr),
     Pat(syn::Pat),
     Type(syn::Type),
+    Item(Box<syn::Item>),
+    Named(syn::token::Brace, Punctuated<NamedItem, Token![,]>),
+    Positional(syn::token::Paren, Punctuated<Self, Token![,]>),
+    Verbatim(proc_macro2::TokenStream),
+}
+
+/// Named associated syntax item: `name = <item>`.
+/// Available as the `$assoc.name` placeholder.
+#[derive(Clone)]
+pub struct NamedItem {
+    pub name: syn::Ident,
+    pub eq_token: Token![=],
+    pub value: Associated,
 }
 
 impl AsRef<str> for Associated {
@@ -34,6 +68,10 @@ impl AsRef<str> for Associated {
             Self::Expr(_) => "expression",
             Self::Pat(_) => "pattern",
             Self::Type(_) => "type",
+            Self::Item(_) => "item",
+            Self::Named(..) => "named list",
+            Self::Positional(..) => "positional list",
+            Self::Verbatim(_) => "tokens with placeholders",
         }
     }
 }
@@ -50,20 +88,214 @@ impl quote::ToTokens for Associated {
             Self::Expr(expr) => expr.to_tokens(tokens),
             Self::Pat(pat) => pat.to_tokens(tokens),
             Self::Type(ty) => ty.to_tokens(tokens),
+            Self::Item(item) => item.to_tokens(tokens),
+            Self::Named(brace_token, items) => {
+                brace_token.surround(tokens, |tokens| items.to_tokens(tokens));
+            }
+            Self::Positional(paren_token, items) => {
+                paren_token.surround(tokens, |tokens| items.to_tokens(tokens));
+            }
+            Self::Verbatim(ts) => ts.to_tokens(tokens),
         }
     }
 }
 
+impl quote::ToTokens for NamedItem {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.name.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+impl NamedItem {
+    /// Parse a named item, where `sigil` introduces placeholders.
+    fn parse_with(input: ParseStream<'_>, sigil: char) -> syn::Result<Self> {
+        Ok(Self {
+            name: input.parse()?,
+            eq_token: input.parse()?,
+            value: Associated::parse_with(input, sigil)?,
+        })
+    }
+}
+
 impl Associated {
+    /// Look up a named item, if this is a named list.
+    pub fn named(&self, name: &syn::Ident) -> Option<&Self> {
+        match self {
+            Self::Named(_, items) => items
+                .iter()
+                .find(|item| item.name == *name)
+                .map(|item| &item.value),
+            _ => None,
+        }
+    }
+
+    /// Look up a positional item, if this is a positional list.
+    ///
+    /// Returns the number of items on failure.
+    pub fn positional(&self, index: usize) -> Option<Result<&Self, usize>> {
+        match self {
+            Self::Positional(_, items) => Some(items.iter().nth(index).ok_or(items.len())),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the cursor is at the end of an associated syntax item,
+    /// i.e. before the next entry, the entry guard or the entry body.
+    fn is_item_end(cursor: Cursor<'_>) -> bool {
+        let is_fat_arrow = |(p, next): (proc_macro2::Punct, Cursor<'_>)| {
+            p.as_char() == '='
+                && p.spacing() == Spacing::Joint
+                && next.punct().is_some_and(|(p, _)| p.as_char() == '>')
+        };
+        cursor.eof()
+            || cursor.punct().is_some_and(|(p, _)| p.as_char() == ',')
+            || cursor.punct().is_some_and(is_fat_arrow)
+            || cursor.ident().is_some_and(|(ident, _)| ident == "if")
+    }
+
+    /// Returns `true` if the input starts with a braced list of named items
+    /// that spans the entire associated syntax item.
+    fn peek_named(input: ParseStream<'_>) -> bool {
+        let Some((content, _, rest)) = input.cursor().group(Delimiter::Brace) else {
+            return false;
+        };
+        // `name = ...`, but not `name == ...` or `name => ...`.
+        let starts_named = content.ident().is_some_and(|(_, next)| {
+            next.punct()
+                .is_some_and(|(p, _)| p.as_char() == '=' && p.spacing() == Spacing::Alone)
+        });
+        starts_named && Self::is_item_end(rest)
+    }
+
+    /// Returns `true` if the input starts with a parenthesized list
+    /// that spans the entire associated syntax item.
+    fn peek_positional(input: ParseStream<'_>) -> bool {
+        input
+            .cursor()
+            .group(Delimiter::Parenthesis)
+            .is_some_and(|(_, _, rest)| Self::is_item_end(rest))
+    }
+
+    /// Parse a braced list of named items: `{ name = <item>, ... }`.
+    fn parse_named(input: ParseStream<'_>, sigil: char) -> syn::Result<Self> {
+        let content;
+        let brace_token = syn::braced!(content in input);
+        let items = parse_terminated(&content, |input| NamedItem::parse_with(input, sigil))?;
+        for (i, item) in items.iter().enumerate() {
+            if items.iter().take(i).any(|prev| prev.name == item.name) {
+                return Err(syn::Error::new(
+                    item.name.span(),
+                    format!("duplicate associated item name `{}`", item.name),
+                ));
+            }
+        }
+        Ok(Self::Named(brace_token, items))
+    }
+
+    /// Parse a parenthesized list of positional items: `(<item>, ...)`.
+    fn parse_positional(input: ParseStream<'_>, sigil: char) -> syn::Result<Self> {
+        let content;
+        let paren_token = syn::parenthesized!(content in input);
+        let items = parse_terminated(&content, |input| Self::parse_with(input, sigil))?;
+        Ok(Self::Positional(paren_token, items))
+    }
+
+    /// Returns the kind prefix (`expr`, `pat`, `type` or `item`) the input starts with, if any.
+    ///
+    /// The prefix must be followed by more tokens before the end of the item,
+    /// so that e.g. a lone `item` is still parsed as an expression.
+    ///
+    /// Unlike `type`, the other prefixes are also valid identifiers. They are only a prefix if the
+    /// whole item does not parse as an expression, so that e.g. `item.len()` or `expr * 2` keep
+    /// referring to a variable.
+    fn peek_kind(input: ParseStream<'_>) -> Option<String> {
+        let (ident, rest) = input.cursor().ident()?;
+        let kind = ident.to_string();
+        if !KINDS.contains(&kind.as_str()) || Self::is_item_end(rest) {
+            return None;
+        }
+        let is_expr = kind != "type"
+            && Self::try_parse_as(&input.fork(), syn::Expr::parse, "expression").is_ok();
+        (!is_expr).then_some(kind)
+    }
+
+    /// Parse an associated syntax item after a kind prefix, using only the parser for that kind.
+    fn parse_kind(input: ParseStream<'_>, kind: &str, sigil: char) -> syn::Result<Self> {
+        let _prefix: proc_macro2::Ident = input.call(syn::ext::IdentExt::parse_any)?;
+        let result = match kind {
+            "expr" => Self::try_parse_as(input, syn::Expr::parse, "expression").map(Self::Expr),
+            "pat" => Self::try_parse_as(input, syn::Pat::parse_multi_with_leading_vert, "pattern")
+                .map(Self::Pat),
+            "type" => Self::try_parse_as(input, syn::Type::parse, "type").map(Self::Type),
+            "item" => Self::try_parse_as(input, syn::Item::parse, "item")
+                .map(|item| Self::Item(Box::new(item))),
+            _ => unreachable!("unknown kind prefix: {kind}"),
+        };
+        match result {
+            // Tokens with placeholders are classified after substitution.
+            Err(_) if Self::contains_sigil(input.cursor(), sigil) => Self::parse_verbatim(input),
+            result => result,
+        }
+    }
+
+    /// Collect the token trees of the associated syntax item at the cursor.
+    ///
+    /// The first token tree is always part of the item, so that it may start with `if`.
+    fn item_token_trees(cursor: Cursor<'_>) -> (proc_macro2::TokenStream, Cursor<'_>) {
+        let mut tokens = proc_macro2::TokenStream::new();
+        let mut rest = cursor;
+        while tokens.is_empty() || !Self::is_item_end(rest) {
+            match rest.token_tree() {
+                Some((TokenTree::Punct(p), _)) if p.as_char() == ',' => break,
+                Some((tt, next)) => {
+                    tokens.extend([tt]);
+                    rest = next;
+                }
+                None => break,
+            }
+        }
+        (tokens, rest)
+    }
+
+    /// Returns `true` if the associated syntax item at the cursor contains the placeholder sigil.
+    fn contains_sigil(cursor: Cursor<'_>, sigil: char) -> bool {
+        fn in_stream(stream: proc_macro2::TokenStream, sigil: char) -> bool {
+            stream.into_iter().any(|tt| match tt {
+                TokenTree::Punct(p) => p.as_char() == sigil,
+                TokenTree::Group(g) => in_stream(g.stream(), sigil),
+                _ => false,
+            })
+        }
+        in_stream(Self::item_token_trees(cursor).0, sigil)
+    }
+
+    /// Parse the token trees of an associated syntax item without classifying them.
+    fn parse_verbatim(input: ParseStream<'_>) -> syn::Result<Self> {
+        input
+            .step(|cursor| Ok(Self::item_token_trees(*cursor)))
+            .map(Self::Verbatim)
+    }
+
     /// Forks the input stream and attempts to parse as type `T`.
     /// If successful, returns the parsed value wrapped in `Self`.
+    ///
+    /// The attempt only succeeds if it consumes the entire associated syntax item.
     fn try_parse_as<T>(
         input: ParseStream<'_>,
-        parse_fn: fn(ParseStream<'_>) -> syn::Result<T>,
+        parse_fn: impl FnOnce(ParseStream<'_>) -> syn::Result<T>,
         what: &str,
     ) -> syn::Result<T> {
         let fork = input.fork();
-        match parse_fn(&fork) {
+        let result = parse_fn(&fork).and_then(|t| {
+            if Self::is_item_end(fork.cursor()) {
+                Ok(t)
+            } else {
+                Err(fork.error("unexpected tokens after associated syntax item"))
+            }
+        });
+        match result {
             Ok(t) => {
                 input.advance_to(&fork);
                 Ok(t)
@@ -76,8 +308,24 @@ impl Associated {
     }
 }
 
-impl syn::parse::Parse for Associated {
-    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+impl Associated {
+    /// Parse an associated syntax item, where `sigil` introduces placeholders.
+    pub fn parse_with(input: ParseStream<'_>, sigil: char) -> syn::Result<Self> {
+        // An explicit kind takes precedence over everything else.
+        if let Some(kind) = Self::peek_kind(input) {
+            return Self::parse_kind(input, &kind, sigil);
+        }
+        // Named list takes precedence over a block expression.
+        if Self::peek_named(input) {
+            return Self::parse_named(input, sigil);
+        }
+        // Positional list, if every item can be parsed on its own.
+        if Self::peek_positional(input) {
+            let parse_positional = |input: ParseStream<'_>| Self::parse_positional(input, sigil);
+            if let Ok(positional) = Self::try_parse_as(input, parse_positional, "list") {
+                return Ok(positional);
+            }
+        }
         // Try expression.
         let expr_err = match Self::try_parse_as(input, syn::Expr::parse, "expression") {
             Ok(expr) => return Ok(Self::Expr(expr)),
@@ -94,6 +342,10 @@ impl syn::parse::Parse for Associated {
             Ok(ty) => return Ok(Self::Type(ty)),
             Err(e) => e,
         };
+        // Tokens with placeholders are classified after substitution.
+        if Self::contains_sigil(input.cursor(), sigil) {
+            return Self::parse_verbatim(input);
+        }
         // All attempts failed, emit errors.
         let err = type_err
             .wrap_err(pat_err)
@@ -105,3 +357,19 @@ impl syn::parse::Parse for Associated {
         Err(err)
     }
 }
+
+/// Like [`Punctuated::parse_terminated_with`], but accepts a closure.
+fn parse_terminated<T>(
+    input: ParseStream<'_>,
+    parse_fn: impl Fn(ParseStream<'_>) -> syn::Result<T>,
+) -> syn::Result<Punctuated<T, Token![,]>> {
+    let mut items = Punctuated::new();
+    while !input.is_empty() {
+        items.push_value(parse_fn(input)?);
+        if input.is_empty() {
+            break;
+        }
+        items.push_punct(input.parse()?);
+    }
+    Ok(items)
+}
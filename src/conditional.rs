@@ -0,0 +1,79 @@
+//! Conditions of the `$if(...) { ... } $else { ... }` construct.
+//!
+//! Conditions are evaluated per entry during substitution, so the branch that is not taken never
+//! reaches the compiler.
+
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{ext::IdentExt as _, parse::ParseStream, punctuated::Punctuated, Token};
+
+use crate::substitute::Context;
+
+const ENTRY: &str = "entry";
+const INDEX: &str = "index";
+const IS_FIRST: &str = "is_first";
+const IS_LAST: &str = "is_last";
+
+/// Evaluate a condition for the entry of `cx`.
+///
+/// Supported conditions:
+/// - `entry = A | B`, `entry != A | B` &mdash; compare the [entry identifier](crate::delegate_entry::DelegateEntry::ident).
+/// - `index = 0 | 1`, `index != 0 | 1` &mdash; compare the index of the entry inside its group.
+/// - `is_first`, `is_last`, optionally negated with `!`.
+pub fn eval(condition: TokenStream2, cx: &Context<'_>) -> syn::Result<bool> {
+    syn::parse::Parser::parse2(
+        |input: ParseStream<'_>| parse_condition(input, cx),
+        condition,
+    )
+}
+
+fn parse_condition(input: ParseStream<'_>, cx: &Context<'_>) -> syn::Result<bool> {
+    let negated = input.peek(Token![!]) && !input.peek(Token![!=]);
+    if negated {
+        input.parse::<Token![!]>()?;
+    }
+    let name = input
+        .call(syn::Ident::parse_any)
+        .map_err(|e| expected_condition(e.span()))?;
+    match name.to_string().as_str() {
+        IS_FIRST => Ok(cx.is_first() != negated),
+        IS_LAST => Ok(cx.is_last() != negated),
+        ENTRY | INDEX if !negated => {
+            let equal = parse_comparison(input)?;
+            let matched = if name == ENTRY {
+                let entry = cx.entry.ident()?;
+                let alternatives =
+                    Punctuated::<syn::Ident, Token![|]>::parse_separated_nonempty(input)?;
+                alternatives.iter().any(|ident| *ident == entry)
+            } else {
+                let alternatives =
+                    Punctuated::<syn::LitInt, Token![|]>::parse_separated_nonempty(input)?;
+                alternatives
+                    .iter()
+                    .map(syn::LitInt::base10_parse::<usize>)
+                    .collect::<syn::Result<Vec<_>>>()?
+                    .contains(&cx.index)
+            };
+            Ok(matched == equal)
+        }
+        _ => Err(expected_condition(name.span())),
+    }
+}
+
+/// Parse `=` or `!=`, returning `true` for equality.
+fn parse_comparison(input: ParseStream<'_>) -> syn::Result<bool> {
+    if input.peek(Token![!=]) {
+        input.parse::<Token![!=]>()?;
+        Ok(false)
+    } else {
+        input.parse::<Token![=]>()?;
+        Ok(true)
+    }
+}
+
+fn expected_condition(span: proc_macro2::Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "expected one of the following conditions: `entry = ...`, `entry != ...`, `index = ...`, \
+         `index != ...`, `is_first`, `is_last`",
+    )
+}
@@ -0,0 +1,151 @@
+//! Per-invocation configuration of the `delegate_match!` macro.
+//!
+//! Options are set with `#![delegate(...)]` inner attributes placed inside the `match` braces,
+//! or with `#[delegate(...)]` outer attributes of a single delegate arm.
+//! These attributes are consumed by the macro and never reach the compiler.
+
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use syn::meta::ParseNestedMeta;
+
+/// Name of the attribute that carries configuration options.
+const ATTR: &str = "delegate";
+
+/// Default limit on the number of arms generated from a single delegate arm.
+const DEFAULT_MAX_ARMS: usize = 1024;
+
+/// Characters that may be used as the placeholder sigil.
+const SIGILS: &[char] = &['$', '#', '%', '@', '~'];
+
+/// Options that control how delegate arms are expanded.
+#[derive(Clone)]
+pub struct Config {
+    /// Substitute placeholders inside string literals.
+    pub interpolate_strings: bool,
+    /// Character that introduces a placeholder, `$` by default.
+    pub sigil: char,
+    /// Require all associated items of an arm to be of the same kind.
+    pub uniform_assoc: bool,
+    /// Maximum number of arms a delegate arm with multiple groups may expand to.
+    pub max_arms: usize,
+    /// Pair the i-th entries of all groups of an arm, instead of expanding to every combination.
+    pub zip: bool,
+    /// Body of a `_ => <body>` arm generated after the arms of a delegate arm.
+    pub mismatch: Option<syn::Expr>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            interpolate_strings: false,
+            sigil: '$',
+            uniform_assoc: false,
+            max_arms: DEFAULT_MAX_ARMS,
+            zip: false,
+            mismatch: None,
+        }
+    }
+}
+
+impl Config {
+    /// Remove all `delegate` attributes from `attrs` and parse the options they contain.
+    pub fn take_from(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Self> {
+        let mut config = Self::default();
+        config.take_options(attrs)?;
+        Ok(config)
+    }
+
+    /// Remove all `delegate` attributes from the raw tokens of outer attributes, and return a copy
+    /// of this config with the options they contain applied.
+    ///
+    /// The remaining attributes are kept as raw tokens, since they may contain placeholders.
+    pub fn take_from_raw(&self, attrs: &mut TokenStream2) -> syn::Result<Self> {
+        let mut config = self.clone();
+        let mut options = Vec::new();
+        let mut kept = TokenStream2::new();
+        let mut tts = attrs.clone().into_iter();
+        while let (Some(pound), Some(bracketed)) = (tts.next(), tts.next()) {
+            let is_option = matches!(
+                &bracketed,
+                TokenTree::Group(group)
+                    if matches!(group.stream().into_iter().next(), Some(TokenTree::Ident(ident)) if ident == ATTR)
+            );
+            let attr = TokenStream2::from_iter([pound, bracketed]);
+            if is_option {
+                options.extend(syn::parse::Parser::parse2(
+                    syn::Attribute::parse_outer,
+                    attr,
+                )?);
+            } else {
+                kept.extend(attr);
+            }
+        }
+        config.take_options(&mut options)?;
+        *attrs = kept;
+        Ok(config)
+    }
+
+    /// Remove all `delegate` attributes from `attrs` and apply the options they contain.
+    fn take_options(&mut self, attrs: &mut Vec<syn::Attribute>) -> syn::Result<()> {
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident(ATTR) {
+                return true;
+            }
+            if let Err(e) = attr.parse_nested_meta(|meta| self.parse_option(&meta)) {
+                match &mut result {
+                    Ok(()) => result = Err(e),
+                    Err(err) => err.combine(e),
+                }
+            }
+            false
+        });
+        result
+    }
+
+    /// Parse a single option, e.g. `interpolate_strings` or `sigil = "@"`.
+    fn parse_option(&mut self, meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+        if meta.path.is_ident("interpolate_strings") {
+            self.interpolate_strings = true;
+            Ok(())
+        } else if meta.path.is_ident("uniform_assoc") {
+            self.uniform_assoc = true;
+            Ok(())
+        } else if meta.path.is_ident("sigil") {
+            self.sigil = Self::parse_sigil(meta)?;
+            Ok(())
+        } else if meta.path.is_ident("zip") {
+            self.zip = true;
+            Ok(())
+        } else if meta.path.is_ident("mismatch") {
+            self.mismatch = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("max_arms") {
+            let lit: syn::LitInt = meta.value()?.parse()?;
+            self.max_arms = lit.base10_parse()?;
+            Ok(())
+        } else {
+            Err(meta.error("unknown delegate option"))
+        }
+    }
+
+    /// Parse the value of the `sigil` option: a string literal containing a single character.
+    fn parse_sigil(meta: &ParseNestedMeta<'_>) -> syn::Result<char> {
+        let lit: syn::LitStr = meta.value()?.parse()?;
+        let value = lit.value();
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(sigil), None) if SIGILS.contains(&sigil) => Ok(sigil),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                format!(
+                    "expected one of the following sigils: {}",
+                    SIGILS
+                        .iter()
+                        .map(|c| format!("`{c}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+        }
+    }
+}
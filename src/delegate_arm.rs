@@ -1,31 +1,51 @@
 //! Implementation of the *grouped* arm syntax.
 
-use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use proc_macro2::{Delimiter, Group, Span, TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt as _};
 use syn::{parse::ParseStream, spanned::Spanned as _, Token};
 
 use crate::{
-    delegate_entry::DelegateEntry,
-    expr::NeedsCommaAsArmBody as _,
+    config::Config,
+    delegate_group::{angle_depth_change, DelegateGroup, SameEntry},
+    expr::{peek_invisible_group, NeedsCommaAsArmBody as _},
+    substitute::Context,
     util::{debug_trace, SynErrorContext as _},
 };
 
 /// The special grouped arm syntax handled by the `delegate_match!` macro:
 /// `path::{ Foo[: bar], ... } [pat] [if guard] => body[,]`
+///
+/// Groups may also appear anywhere inside the pattern, e.g. `Some(E::{ A, B }(x))`, and there
+/// may be several of them, e.g. `(A::{ X, Y }(a), B::{ Z }(b))`, which expands to every
+/// combination of their entries.
 #[derive(Clone)]
 pub struct DelegateArm {
-    pub attrs: Vec<syn::Attribute>,
-    pub path: Option<syn::Path>,
-    pub path_sep: Option<Token![::]>,
-    pub _brace_token: syn::token::Brace,
-    pub entries: Vec<DelegateEntry>,
-    pub pat: Option<TokenStream2>,
+    /// Raw tokens of the outer attributes. These are parsed after placeholder substitution.
+    pub attrs: TokenStream2,
+    /// Entry groups, in the order they appear in the pattern.
+    pub groups: Vec<DelegateGroup>,
+    /// The pattern, with its groups taken out.
+    pub pattern: Vec<ArmPattern>,
     pub guard: Option<(Token![if], TokenStream2)>,
     pub fat_arrow_token: Token![=>],
     /// Raw token stream of the match-arm body. We postpone actual `syn::Expr` parsing until after
     /// placeholder substitution.
     pub body: TokenStream2,
     pub comma: Option<Token![,]>,
+    pub config: Config,
+}
+
+/// Part of the pattern of a delegate arm.
+#[derive(Clone)]
+pub enum ArmPattern {
+    /// A group, referred to by its index in the arm.
+    Group(usize),
+    /// `[path::]$same [pat]`, the current entry of a group at another position.
+    Same(SameEntry),
+    /// Delimited tokens that contain groups, e.g. `( ... )` in `Some( ... )`.
+    Delimited(Delimiter, Span, Vec<Self>),
+    /// Regular pattern tokens, which may contain placeholders.
+    Tokens(TokenStream2),
 }
 
 impl ToTokens for DelegateArm {
@@ -45,122 +65,163 @@ impl ToTokens for DelegateArm {
     }
 }
 
-impl syn::parse::Parse for DelegateArm {
-    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+impl DelegateArm {
+    /// Parse a delegate arm that is expanded according to `config`.
+    pub fn parse_with(input: ParseStream<'_>, config: &Config) -> syn::Result<Self> {
         debug_trace!("parsing arm");
-        let attrs = input.call(syn::Attribute::parse_outer)?;
-        debug_trace!("parsing path");
-        let path = Self::parse_path(input)?;
-        let path_sep = Self::parse_path_sep(input, path.as_ref())?;
-        debug_trace!("parsing entries");
-        let (brace_token, entries) = Self::parse_entries(input)?;
-        let pat = Self::parse_pat(input)?;
+        let mut attrs = Self::parse_attrs(input)?;
+        // Options of this arm only, e.g. `#[delegate(zip)]`.
+        let config = &config.take_from_raw(&mut attrs)?;
+        let mut groups = Vec::new();
+        let pattern = Self::parse_pattern(input, config, &mut groups)?;
         let guard = Self::parse_guard(input)?;
         let fat_arrow_token = input.parse()?;
         debug_trace!("parsing body tokens");
-        let body = crate::expr::parse_tokens(input)
+        let body = crate::expr::parse_tokens(input, config.sigil)
             .wrap_err(input.error("failed to parse body tokens"))?;
         debug_trace!("parsed body tokens: {body}");
         let comma = input.parse()?;
         Ok(Self {
             attrs,
-            path,
-            path_sep,
-            _brace_token: brace_token,
-            entries,
-            pat,
+            groups,
+            pattern,
             guard,
             fat_arrow_token,
             body,
             comma,
+            config: config.clone(),
         })
     }
 }
 
 impl DelegateArm {
-    /// Parse the optional path (e.g. `::abc::SomeEnum`) before `::{`.
-    fn parse_path(input: ParseStream<'_>) -> syn::Result<Option<syn::Path>> {
-        if input.peek(syn::token::Brace) {
-            return Ok(None);
+    /// Parse the pattern of the arm, adding its groups to `groups`.
+    fn parse_pattern(
+        input: ParseStream<'_>,
+        config: &Config,
+        groups: &mut Vec<DelegateGroup>,
+    ) -> syn::Result<Vec<ArmPattern>> {
+        let tokens = Self::parse_tokens_until(input, |input| {
+            input.peek(Token![if]) || input.peek(Token![=>])
+        })?;
+        let span = tokens.span();
+        let pattern = Self::parse_pattern_parts(tokens, config, groups)?;
+        if groups.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "expected at least one `{ ... }` entry group in the pattern",
+            ));
         }
-        let mut tokens: Vec<TokenTree> = vec![];
-        while !input.is_empty() {
-            if input.peek(Token![::]) && input.peek3(syn::token::Brace) {
-                if tokens.is_empty() {
-                    return Err(syn::Error::new(
-                        input.span(),
-                        "found leading path separator, expected non-crate path",
-                    ));
+        Ok(pattern)
+    }
+
+    /// Take the groups out of pattern tokens, at any depth.
+    ///
+    /// The tokens are split into elements at top-level commas. Each element may contain one
+    /// group or `$same`, which extends to the end of the element, e.g. `E::{ A, B }(x)` in
+    /// `&E::{ A, B }(x), y`. Otherwise, delimited tokens are searched for groups.
+    fn parse_pattern_parts(
+        tokens: TokenStream2,
+        config: &Config,
+        groups: &mut Vec<DelegateGroup>,
+    ) -> syn::Result<Vec<ArmPattern>> {
+        let tts: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut parts = Vec::new();
+        let mut plain = TokenStream2::new();
+        for (element, comma) in Self::split_elements(&tts) {
+            if let Some(start) = DelegateGroup::find_start(element) {
+                plain.extend(element[..start].iter().cloned());
+                parts.extend(Self::take_plain(&mut plain));
+                let group = syn::parse::Parser::parse2(
+                    |input: ParseStream<'_>| DelegateGroup::parse_with(input, config),
+                    element[start..].iter().cloned().collect(),
+                )?;
+                groups.push(group);
+                parts.push(ArmPattern::Group(groups.len() - 1));
+            } else if let Some((start, same)) = SameEntry::parse_from(element, config.sigil)? {
+                plain.extend(element[..start].iter().cloned());
+                parts.extend(Self::take_plain(&mut plain));
+                parts.push(ArmPattern::Same(same));
+            } else {
+                for tt in element {
+                    match tt {
+                        TokenTree::Group(group) if group.delimiter() != Delimiter::None => {
+                            let inner = Self::parse_pattern_parts(group.stream(), config, groups)?;
+                            if inner
+                                .iter()
+                                .all(|part| matches!(part, ArmPattern::Tokens(_)))
+                            {
+                                plain.extend([tt.clone()]);
+                            } else {
+                                parts.extend(Self::take_plain(&mut plain));
+                                parts.push(ArmPattern::Delimited(
+                                    group.delimiter(),
+                                    group.span(),
+                                    inner,
+                                ));
+                            }
+                        }
+                        _ => plain.extend([tt.clone()]),
+                    }
                 }
-                break;
             }
-            let tt = input.parse()?;
-            tokens.push(tt);
+            plain.extend(comma.cloned());
         }
-        let ts: TokenStream2 = tokens.into_iter().collect();
-        debug_trace!("parsed path: {ts}");
-        syn::parse2(ts)
-            .map(Some)
-            .wrap_err(input.error("failed to parse delegate arm path"))
+        parts.extend(Self::take_plain(&mut plain));
+        Ok(parts)
     }
 
-    /// Parse the `{ ... }` part that contains one or more [`DelegateEntry`] items.
-    fn parse_entries(
-        input: ParseStream<'_>,
-    ) -> syn::Result<(syn::token::Brace, Vec<DelegateEntry>)> {
-        let content;
-        let brace_token = syn::braced!(content in input);
-        let entries = DelegateEntry::parse_multiple(&content).wrap_err(syn::Error::new(
-            content.span(),
-            "failed to parse delegate arm entry",
-        ))?;
-        Ok((brace_token, entries))
+    /// Split the tokens of a pattern at top-level commas into elements and their trailing commas.
+    ///
+    /// Commas inside generic arguments (e.g. `Either::<u8, u16>::{ ... }`) do not split.
+    fn split_elements(tts: &[TokenTree]) -> Vec<(&[TokenTree], Option<&TokenTree>)> {
+        let mut elements = Vec::new();
+        let mut start = 0;
+        let mut depth: isize = 0;
+        for (i, tt) in tts.iter().enumerate() {
+            depth = (depth + angle_depth_change(tts, i)).max(0);
+            if depth == 0 && matches!(tt, TokenTree::Punct(p) if p.as_char() == ',') {
+                elements.push((&tts[start..i], Some(tt)));
+                start = i + 1;
+            }
+        }
+        if start < tts.len() {
+            elements.push((&tts[start..], None));
+        }
+        elements
     }
 
-    /// Parse tokens until the given predicate returns `true`.
-    fn parse_tokens_until<F>(input: ParseStream<'_>, mut f: F) -> syn::Result<TokenStream2>
-    where
-        F: FnMut(ParseStream<'_>, &TokenTree) -> bool,
-    {
-        let mut tokens = Vec::<TokenTree>::new();
-        while !input.is_empty() {
-            let tt: TokenTree = input.parse()?;
-            let is_end = f(input, &tt);
-            tokens.push(tt);
-            if is_end {
-                break;
-            }
+    /// Take the collected regular tokens as a part of the pattern, if there are any.
+    fn take_plain(plain: &mut TokenStream2) -> Option<ArmPattern> {
+        (!plain.is_empty()).then(|| ArmPattern::Tokens(std::mem::take(plain)))
+    }
+
+    /// Parse the raw tokens of outer attributes: `#[...]*`.
+    pub(crate) fn parse_attrs(input: ParseStream<'_>) -> syn::Result<TokenStream2> {
+        let mut attrs = TokenStream2::new();
+        while input.peek(Token![#]) && input.peek2(syn::token::Bracket) {
+            let pound: TokenTree = input.parse()?;
+            let bracketed: TokenTree = input.parse()?;
+            attrs.extend([pound, bracketed]);
         }
-        Ok(tokens.into_iter().collect())
+        debug_trace!("parsed attrs: {attrs}");
+        Ok(attrs)
     }
 
-    /// Parse tokens while tracking delimiter depth.
-    fn parse_tokens_with_depth<F>(input: ParseStream<'_>, mut f: F) -> syn::Result<TokenStream2>
+    /// Parse token trees until the given predicate returns `true` for the remaining input.
+    ///
+    /// Delimited groups are consumed as a whole, so the predicate only sees top-level tokens.
+    /// This includes invisible groups (forwarded `macro_rules!` fragments), which the predicate is
+    /// never asked about, since peeking would look at their first token instead.
+    pub(crate) fn parse_tokens_until<F>(input: ParseStream<'_>, f: F) -> syn::Result<TokenStream2>
     where
-        F: FnMut(ParseStream<'_>, &TokenTree, i32) -> bool,
+        F: Fn(ParseStream<'_>) -> bool,
     {
-        let mut depth: i32 = 0;
-        Self::parse_tokens_until(input, |input, tt| {
-            if let TokenTree::Punct(p) = tt {
-                match p.as_char() {
-                    '(' | '[' | '{' => depth += 1,
-                    ')' | ']' | '}' => depth -= 1,
-                    _ => {}
-                }
-            }
-            f(input, tt, depth)
-        })
-    }
-
-    /// Parse an optional pattern that follows the entry list.
-    fn parse_pat(input: ParseStream<'_>) -> syn::Result<Option<TokenStream2>> {
-        if input.peek(Token![if]) || input.peek(Token![=>]) {
-            return Ok(None);
+        let mut tokens = Vec::<TokenTree>::new();
+        while !input.is_empty() && (peek_invisible_group(input) || !f(input)) {
+            tokens.push(input.parse()?);
         }
-        let tokens = Self::parse_tokens_with_depth(input, |input, _tt, depth| {
-            depth == 0 && (input.peek(Token![if]) || input.peek(Token![=>]))
-        })?;
-        Ok(Some(tokens))
+        Ok(tokens.into_iter().collect())
     }
 
     /// Parse an optional `if <expr>` guard that can accompany a match arm.
@@ -169,68 +230,201 @@ impl DelegateArm {
             return Ok(None);
         }
         let if_token: Token![if] = input.parse()?;
-        let tokens = Self::parse_tokens_with_depth(input, |input, _tt, depth| {
-            depth == 0 && input.peek(Token![=>])
-        })?;
-        Ok(Some((if_token, tokens.into_iter().collect())))
+        let tokens = Self::parse_tokens_until(input, |input| input.peek(Token![=>]))?;
+        Ok(Some((if_token, tokens)))
     }
 
-    /// Parse a `::` token if outer path is present.
-    fn parse_path_sep(
-        input: ParseStream<'_>,
-        outer_path: Option<&syn::Path>,
-    ) -> syn::Result<Option<Token![::]>> {
-        if outer_path.is_some() {
-            Ok(Some(input.parse()?))
-        } else {
-            Ok(None)
+    /// Expand the grouped delegate arm into a list of concrete [`syn::Arm`]s.
+    fn build_arms(&self) -> syn::Result<Vec<syn::Arm>> {
+        let combinations = self.combinations()?;
+        let count = combinations.len();
+        let mut arms = combinations
+            .iter()
+            .enumerate()
+            .map(|(i, combination)| {
+                let is_last_arm = i + 1 == count && self.config.mismatch.is_none();
+                self.build_arm_with(&Context::new(self, combination), is_last_arm)
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        arms.extend(self.build_mismatch_arm());
+        Ok(arms)
+    }
+
+    /// Build the `_ => <mismatch>` arm, if the `mismatch` option is set.
+    fn build_mismatch_arm(&self) -> Option<syn::Arm> {
+        self.config.mismatch.as_ref().map(|body| syn::Arm {
+            attrs: Vec::new(),
+            pat: syn::Pat::Wild(syn::PatWild {
+                attrs: Vec::new(),
+                underscore_token: Token![_](body.span()),
+            }),
+            guard: None,
+            fat_arrow_token: self.fat_arrow_token,
+            body: Box::new(body.clone()),
+            comma: Some(Token![,](body.span())),
+        })
+    }
+
+    /// Return the entry index of every group for each generated arm, in order.
+    ///
+    /// This is the cartesian product of all groups, where the last group varies fastest.
+    /// In zip mode, the i-th entries of all groups are paired instead.
+    fn combinations(&self) -> syn::Result<Vec<Vec<usize>>> {
+        if self.config.zip {
+            return self.zipped_combinations();
         }
+        let count = self.groups.iter().fold(1_usize, |count, group| {
+            count.saturating_mul(group.entries.len())
+        });
+        // A single group expands to one arm per entry, which is never accidental.
+        if self.groups.len() > 1 && count > self.config.max_arms {
+            let sizes = self
+                .groups
+                .iter()
+                .map(|group| group.entries.len().to_string())
+                .collect::<Vec<_>>()
+                .join(" × ");
+            return Err(syn::Error::new(
+                self.pattern_span(),
+                format!(
+                    "delegate arm expands to {count} arms ({sizes} entries), which exceeds the limit of {}\n\
+                     raise the limit with `#![delegate(max_arms = <n>)]` if this is intended",
+                    self.config.max_arms
+                ),
+            ));
+        }
+        let mut combinations = vec![Vec::new()];
+        for group in &self.groups {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|prefix: Vec<usize>| {
+                    (0..group.entries.len()).map(move |index| {
+                        let mut combination = prefix.clone();
+                        combination.push(index);
+                        combination
+                    })
+                })
+                .collect();
+        }
+        Ok(combinations)
     }
 
-    /// Expand the grouped delegate arm into a list of concrete [`syn::Arm`]s.
-    fn build_arms(&self) -> syn::Result<Vec<syn::Arm>> {
-        let len = self.entries.len();
-        let is_last = |i: usize| i == len - 1;
-        self.entries
+    /// Return the combinations of zip mode, where all groups must have the same length.
+    fn zipped_combinations(&self) -> syn::Result<Vec<Vec<usize>>> {
+        let first = &self.groups[0];
+        let len = first.entries.len();
+        if let Some((number, group)) = self
+            .groups
             .iter()
             .enumerate()
-            .map(|(i, entry)| self.build_arm_with(entry, is_last(i)))
-            .collect()
+            .find(|(_, group)| group.entries.len() != len)
+        {
+            return Err(syn::Error::new(
+                group.brace_token.span.join(),
+                format!(
+                    "cannot zip groups of different lengths: group 1 has {len} entries, but group {} has {}",
+                    number + 1,
+                    group.entries.len()
+                ),
+            ));
+        }
+        Ok((0..len)
+            .map(|index| vec![index; self.groups.len()])
+            .collect())
+    }
+
+    /// Span of the start of the arm pattern, used for errors about the arm as a whole.
+    fn pattern_span(&self) -> Span {
+        match self.pattern.first() {
+            Some(ArmPattern::Group(index)) => self.groups[*index].brace_token.span.join(),
+            Some(ArmPattern::Same(same)) => same.span,
+            Some(ArmPattern::Delimited(_, span, _)) => *span,
+            Some(ArmPattern::Tokens(tokens)) => tokens.span(),
+            None => self.fat_arrow_token.span(),
+        }
+    }
+
+    /// Build the outer attributes for the given entry: the arm's, followed by the entry's own.
+    fn build_attrs_with(&self, cx: &Context<'_>) -> syn::Result<Vec<syn::Attribute>> {
+        let mut attrs = Self::build_substituted_attrs_with(&self.attrs, cx)?;
+        attrs.extend(Self::build_substituted_attrs_with(&cx.entry.attrs, cx)?);
+        Ok(attrs)
+    }
+
+    /// Substitute placeholders in raw attribute tokens, then parse them.
+    fn build_substituted_attrs_with(
+        ts: &TokenStream2,
+        cx: &Context<'_>,
+    ) -> syn::Result<Vec<syn::Attribute>> {
+        let tokens = crate::substitute::substitute(ts, cx)?;
+        syn::parse::Parser::parse2(syn::Attribute::parse_outer, tokens).wrap_err(syn::Error::new(
+            ts.span(),
+            "failed to parse attributes after substitution",
+        ))
     }
 
     /// Build an if-guard for the given entry.
+    ///
+    /// If both the arm and the entry have a guard, they are combined as `(arm) && (entry)`.
     fn build_guard_with(
         &self,
-        entry: &DelegateEntry,
+        cx: &Context<'_>,
     ) -> syn::Result<Option<(Token![if], Box<syn::Expr>)>> {
-        match self.guard {
-            Some((if_tok, ref guard_ts)) => {
-                let guard_expr = Self::build_substituted_expr_with(
+        let arm_guard = Self::build_guard_expr_with(self.guard.as_ref(), cx)?;
+        let entry_guard = Self::build_guard_expr_with(cx.entry.guard.as_ref(), cx)?;
+        let guard = match (arm_guard, entry_guard) {
+            (Some((if_tok, arm)), Some((_, entry))) => {
+                let combined = quote!((#arm) && (#entry));
+                let expr = syn::parse2(combined)?;
+                Some((if_tok, Box::new(expr)))
+            }
+            (Some((if_tok, expr)), None) | (None, Some((if_tok, expr))) => {
+                Some((if_tok, Box::new(expr)))
+            }
+            (None, None) => None,
+        };
+        Ok(guard)
+    }
+
+    /// Build a single guard expression, if present.
+    fn build_guard_expr_with(
+        guard: Option<&(Token![if], TokenStream2)>,
+        cx: &Context<'_>,
+    ) -> syn::Result<Option<(Token![if], syn::Expr)>> {
+        guard
+            .map(|(if_tok, guard_ts)| {
+                Self::build_substituted_expr_with(
                     guard_ts,
                     guard_ts.span(),
                     syn::Expr::parse_with_earlier_boundary_rule,
-                    entry,
+                    cx,
                 )
                 .wrap_err(syn::Error::new(
                     guard_ts.span(),
                     "failed to parse guard expression",
-                ))?;
-                let guard = (if_tok, Box::new(guard_expr));
-                Ok(Some(guard))
-            }
-            None => Ok(None),
-        }
+                ))
+                .map(|expr| (*if_tok, expr))
+            })
+            .transpose()
+    }
+
+    /// Return the body tokens for the given entry.
+    ///
+    /// An entry may override the shared body.
+    fn body_tokens<'b>(&'b self, cx: &Context<'b>) -> &'b TokenStream2 {
+        cx.entry.body.as_ref().map_or(&self.body, |(_, body)| body)
     }
 
     /// Build the body [`syn::Expr`] for the given entry.
-    fn build_body_expr_with(&self, entry: &DelegateEntry) -> syn::Result<Box<syn::Expr>> {
+    fn build_body_expr_with(&self, cx: &Context<'_>) -> syn::Result<Box<syn::Expr>> {
+        let body = self.body_tokens(cx);
         let expr = Self::build_substituted_expr_with(
-            &self.body,
-            self.body.span(),
+            body,
+            body.span(),
             syn::Expr::parse_with_earlier_boundary_rule,
-            entry,
+            cx,
         )
-        .wrap_err_with(|| syn::Error::new(self.body.span(), "failed to parse delegate arm body"))?;
+        .wrap_err_with(|| syn::Error::new(body.span(), "failed to parse delegate arm body"))?;
         Ok(Box::new(expr))
     }
 
@@ -239,27 +433,29 @@ impl DelegateArm {
     fn build_arm_comma_with(
         &self,
         body: &syn::Expr,
-        is_last_entry: bool,
+        cx: &Context<'_>,
+        is_last_arm: bool,
     ) -> Option<syn::token::Comma> {
         self.comma.or_else(|| {
-            let condition = body.needs_comma() && !is_last_entry;
+            let condition = body.needs_comma() && !is_last_arm;
             condition.then(|| {
                 syn::token::Comma {
                     // Use entire body as span for errors.
                     // This is the last spanned item we have access to.
-                    spans: [self.body.span(); 1],
+                    spans: [self.body_tokens(cx).span(); 1],
                 }
             })
         })
     }
 
-    /// Build one concrete [`syn::Arm`] from the template combined with the given `entry`.
-    fn build_arm_with(&self, entry: &DelegateEntry, is_last_entry: bool) -> syn::Result<syn::Arm> {
-        let attrs = self.attrs.clone();
-        let pat = self.build_pattern_with(entry)?;
-        let body = self.build_body_expr_with(entry)?;
-        let guard = self.build_guard_with(entry)?;
-        let comma = self.build_arm_comma_with(&body, is_last_entry);
+    /// Build one concrete [`syn::Arm`] from the template combined with the given entries.
+    fn build_arm_with(&self, cx: &Context<'_>, is_last_arm: bool) -> syn::Result<syn::Arm> {
+        let attrs = self.build_attrs_with(cx)?;
+        let nested = self.pattern.len() > 1;
+        let pat = syn::Pat::Verbatim(Self::build_pattern_with(&self.pattern, cx, nested)?);
+        let body = self.build_body_expr_with(cx)?;
+        let guard = self.build_guard_with(cx)?;
+        let comma = self.build_arm_comma_with(&body, cx, is_last_arm);
         Ok(syn::Arm {
             attrs,
             pat,
@@ -270,75 +466,55 @@ impl DelegateArm {
         })
     }
 
-    /// Combine path, the current entry pattern and pattern into the
-    /// final pattern of the generated arm.
-    fn build_pattern_with(&self, entry: &DelegateEntry) -> syn::Result<syn::Pat> {
-        // Perform substitution on the arm pattern, if available.
-        let arm_pat_ts = self.pat.as_ref().map(|ts| {
-            crate::substitute::substitute(ts, &entry.pat, entry.associated_tokens().as_ref())
-        });
-        Self::build_final_pattern(
-            self.path.as_ref(),
-            self.path_sep.as_ref(),
-            &entry.pat,
-            arm_pat_ts.as_ref(),
-        )
-    }
-
-    /// Build the final pattern of the generated match arm.
+    /// Build the final pattern of the generated arm, replacing each group with its current entry.
     ///
-    /// This function recursively handles or-patterns (`A | B`) by applying the
-    /// path and arm pattern transformation to each alternative, then recombining
-    /// them.
-    /// For example, `Test::{ A, B | C }(case)` produces `Test::B(case) | Test::C(case)`
-    /// as the second generated match arm.
-    fn build_final_pattern(
-        path: Option<&syn::Path>,
-        path_sep: Option<&Token![::]>,
-        entry_pat: &syn::Pat,
-        arm_pat_ts: Option<&TokenStream2>,
-    ) -> syn::Result<syn::Pat> {
-        // How a regular match arm pattern is built.
-        let verbatim_join = || syn::Pat::Verbatim(quote!(#path #path_sep #entry_pat #arm_pat_ts));
-        // Build the final pattern.
-        #[allow(
-            clippy::match_same_arms,
-            reason = "loses semantic distinction between cases"
-        )]
-        match (&entry_pat, &arm_pat_ts) {
-            // Or-pattern: recursively transform each alternative and recombine.
-            (syn::Pat::Or(or_pat), _) => Self::build_or_pattern(path, path_sep, or_pat, arm_pat_ts),
-            // Fully compatible.
-            (syn::Pat::Ident(_) | syn::Pat::Path(_), _) => Ok(verbatim_join()),
-            // Only build if no arm pattern is present.
-            (syn::Pat::TupleStruct(_) | syn::Pat::Struct(_), None) => Ok(verbatim_join()),
-            // Incompatible. Error if arm pattern is present.
-            (_, Some(_)) => Err(syn::Error::new(
-                entry_pat.span(),
-                "entry pattern incompatible with arm pattern",
-            )),
-            // No arm pattern, so just use the entry pattern.
-            (_, None) => Ok(entry_pat.clone()),
+    /// If `nested`, the parts are not the whole pattern, so or-patterns of entries are
+    /// parenthesized to keep them together, e.g. `&(E::A(x) | E::B(x))`.
+    fn build_pattern_with(
+        pattern: &[ArmPattern],
+        cx: &Context<'_>,
+        nested: bool,
+    ) -> syn::Result<TokenStream2> {
+        let mut tokens = TokenStream2::new();
+        let push = |pat: syn::Pat, tokens: &mut TokenStream2| match pat {
+            syn::Pat::Or(_) if nested => {
+                tokens.append(Group::new(Delimiter::Parenthesis, pat.into_token_stream()));
+            }
+            pat => pat.to_tokens(tokens),
+        };
+        for part in pattern {
+            match part {
+                ArmPattern::Group(index) => {
+                    let cx = cx.with_group(*index);
+                    push(cx.group.build_pattern_with(&cx)?, &mut tokens);
+                }
+                ArmPattern::Same(same) => {
+                    let groups = cx.arm.groups.len();
+                    if same.group_index >= groups {
+                        return Err(syn::Error::new(
+                            same.span,
+                            format!(
+                                "`{}{}.same` does not refer to a group of this arm, expected a number from 1 to {groups}",
+                                cx.arm.config.sigil,
+                                same.group_index + 1,
+                            ),
+                        ));
+                    }
+                    push(
+                        same.build_pattern_with(&cx.with_group(same.group_index))?,
+                        &mut tokens,
+                    );
+                }
+                ArmPattern::Delimited(delimiter, span, inner) => {
+                    let mut group =
+                        Group::new(*delimiter, Self::build_pattern_with(inner, cx, true)?);
+                    group.set_span(*span);
+                    tokens.append(group);
+                }
+                ArmPattern::Tokens(ts) => tokens.extend(crate::substitute::substitute(ts, cx)?),
+            }
         }
-    }
-
-    /// Recursively transform an or-pattern by applying path and arm pattern to each case.
-    fn build_or_pattern(
-        path: Option<&syn::Path>,
-        path_sep: Option<&Token![::]>,
-        or_pat: &syn::PatOr,
-        arm_pat_ts: Option<&TokenStream2>,
-    ) -> syn::Result<syn::Pat> {
-        let cases: syn::punctuated::Punctuated<syn::Pat, Token![|]> = or_pat
-            .cases
-            .iter()
-            .map(|case| Self::build_final_pattern(path, path_sep, case, arm_pat_ts))
-            .collect::<syn::Result<_>>()?;
-        Ok(syn::Pat::Or(syn::PatOr {
-            attrs: or_pat.attrs.clone(),
-            leading_vert: or_pat.leading_vert,
-            cases,
-        }))
+        Ok(tokens)
     }
 
     /// Substitute placeholders in the user-provided body for the given entry.
@@ -346,15 +522,14 @@ impl DelegateArm {
         ts: &TokenStream2,
         span: proc_macro2::Span,
         f: F,
-        entry: &DelegateEntry,
+        cx: &Context<'_>,
     ) -> syn::Result<syn::Expr>
     where
         F: FnOnce(ParseStream<'_>) -> syn::Result<syn::Expr>,
     {
         debug_trace!("tokenizing substituted expr");
         debug_trace!("input: {ts}");
-        let tokens =
-            crate::substitute::substitute(ts, &entry.pat, entry.associated_tokens().as_ref());
+        let tokens = crate::substitute::substitute(ts, cx)?;
         debug_trace!("substituted tokens: {tokens}");
         let expr = syn::parse::Parser::parse2(f, tokens).wrap_err(syn::Error::new(
             span,
@@ -7,37 +7,57 @@ use syn::{parse::ParseStream, spanned::Spanned, Token};
 
 use crate::{
     associated::Associated,
+    config::Config,
+    delegate_arm::DelegateArm,
     util::{debug_trace, SynErrorContext as _},
 };
 
 /// One item inside the entry list of a [`DelegateArm`]: `{ ... }`.
-/// It consists of a pattern plus an optional *associated* token stream after `:`.
+/// It consists of optional outer attributes, a pattern and an optional *associated* token stream
+/// after `:`. The attributes are carried onto the generated arm, after the arm's own attributes.
 /// During expansion, the two are available as `$entry_pat` and `$assoc_ts`
 /// [placeholders](crate::substitute::substitute) inside the delegate arm's body.
+/// Items of a named associated list are available as `$assoc.name`.
 ///
-/// [DelegateArm]: crate::delegate_arm::DelegateArm
+/// Entries may be nested groups themselves, e.g. `Input::{ Key, Mouse }`, which are flattened
+/// into their entries with the path of the nested group recorded as a parent.
 #[derive(Clone)]
 pub struct DelegateEntry {
+    /// Raw tokens of the outer attributes. These are parsed after placeholder substitution.
+    pub attrs: TokenStream2,
     pub pat: syn::Pat,
+    /// Paths of the nested groups this entry is part of, outermost first.
+    pub parents: Vec<syn::Path>,
     pub associated: Option<(Token![:], Associated)>,
+    /// Raw tokens of the entry's own guard, combined with the arm guard.
+    pub guard: Option<(Token![if], TokenStream2)>,
+    /// Raw tokens of a body that overrides the arm body for this entry.
+    pub body: Option<(Token![=>], TokenStream2)>,
     pub _comma: Option<Token![,]>,
 }
 
-impl syn::parse::Parse for DelegateEntry {
-    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+impl DelegateEntry {
+    /// Parse a single entry, including its trailing comma.
+    fn parse_with(input: ParseStream<'_>, config: &Config) -> syn::Result<Self> {
         debug_trace!("parsing entry in: {input}");
+        let attrs = DelegateArm::parse_attrs(input)?;
         let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
         debug_trace!("parsed pat: {}", pat.to_token_stream());
-        let associated = Self::parse_associated(input)?;
+        let associated = Self::parse_associated(input, config)?;
         if let Some((_, associated)) = &associated {
             debug_trace!("parsed associated: {}", associated.to_token_stream());
         } else {
             debug_trace!("no associated tokens");
         }
+        let guard = Self::parse_guard(input)?;
+        let body = Self::parse_body(input, config)?;
         let comma = if input.is_empty() {
             None
         } else if input.peek(Token![,]) {
             Some(input.parse()?)
+        } else if body.as_ref().is_some_and(|(_, body)| ends_with_block(body)) {
+            // Like in a match arm, a block-like body does not need a trailing comma.
+            None
         } else {
             let mut err = syn::Error::new(pat.span(), "expected comma after entry");
             if let Some((_, associated)) = &associated {
@@ -51,23 +71,88 @@ impl syn::parse::Parse for DelegateEntry {
         };
         debug_trace!("parsed entry: {}", pat.to_token_stream());
         Ok(Self {
+            attrs,
             pat,
+            parents: Vec::new(),
             associated,
+            guard,
+            body,
             _comma: comma,
         })
     }
-}
 
-impl DelegateEntry {
     /// Parse a comma-separated list of [`DelegateEntry`] items until the end of `input`.
-    pub(crate) fn parse_multiple(input: ParseStream<'_>) -> syn::Result<Vec<Self>> {
+    pub(crate) fn parse_multiple(
+        input: ParseStream<'_>,
+        config: &Config,
+    ) -> syn::Result<Vec<Self>> {
         let mut v = Vec::new();
         while !input.is_empty() {
-            v.push(input.parse()?);
+            if Self::peek_nested(input) {
+                v.extend(Self::parse_nested(input, config)?);
+            } else {
+                v.push(Self::parse_with(input, config)?);
+            }
         }
         Ok(v)
     }
 
+    /// Return `true` if the next entry is a nested group: `[#[attr]] path::{ ... }`.
+    fn peek_nested(input: ParseStream<'_>) -> bool {
+        let mut cursor = input.cursor();
+        // Skip the attributes.
+        while let Some((_, rest)) = cursor.punct().filter(|(punct, _)| punct.as_char() == '#') {
+            let Some((_, _, rest)) = rest.group(proc_macro2::Delimiter::Bracket) else {
+                return false;
+            };
+            cursor = rest;
+        }
+        while let Some((_, rest)) = cursor.ident() {
+            let Some((first, next)) = rest.punct() else {
+                return false;
+            };
+            let Some((second, next)) = next.punct() else {
+                return false;
+            };
+            if first.as_char() != ':' || second.as_char() != ':' {
+                return false;
+            }
+            if next.group(proc_macro2::Delimiter::Brace).is_some() {
+                return true;
+            }
+            cursor = next;
+        }
+        false
+    }
+
+    /// Parse a nested group of entries, including its trailing comma, and flatten it.
+    ///
+    /// The attributes of the nested group are placed before those of each entry.
+    fn parse_nested(input: ParseStream<'_>, config: &Config) -> syn::Result<Vec<Self>> {
+        let attrs = DelegateArm::parse_attrs(input)?;
+        let path_tokens = DelegateArm::parse_tokens_until(input, |input| {
+            input.peek(Token![::]) && input.peek3(syn::token::Brace)
+        })?;
+        let path = syn::parse::Parser::parse2(syn::Path::parse_mod_style, path_tokens)?;
+        input.parse::<Token![::]>()?;
+        let content;
+        syn::braced!(content in input);
+        let mut entries = Self::parse_multiple(&content, config)?;
+        for entry in &mut entries {
+            entry.parents.insert(0, path.clone());
+            entry.attrs = attrs
+                .clone()
+                .into_iter()
+                .chain(entry.attrs.clone())
+                .collect();
+        }
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        debug_trace!("parsed nested entries: {}", path.to_token_stream());
+        Ok(entries)
+    }
+
     /// Return the user-supplied token stream that followed a `:` after the entry, if available.
     pub(crate) fn associated_tokens(&self) -> Option<TokenStream2> {
         self.associated
@@ -75,15 +160,144 @@ impl DelegateEntry {
             .map(|(_, associated)| associated.to_token_stream())
     }
 
+    /// Like [`associated_tokens`](Self::associated_tokens), but fails if the entry has none.
+    ///
+    /// The error suggests a default placeholder written with `sigil`.
+    pub(crate) fn required_associated_tokens(&self, sigil: char) -> syn::Result<TokenStream2> {
+        self.associated_tokens().ok_or_else(|| {
+            syn::Error::new(
+                self.pat.span(),
+                format!(
+                    "entry `{}` has no associated item, add one with `: <item>` or use `{sigil}assoc_ts(or: <default>)`",
+                    self.pat.to_token_stream()
+                ),
+            )
+        })
+    }
+
+    /// Return the path naming this entry, e.g. `A` for `A(x)` or `A { x }`.
+    ///
+    /// Fails if the entry pattern does not start with a path.
+    pub(crate) fn path(&self) -> syn::Result<syn::Path> {
+        match &self.pat {
+            syn::Pat::Ident(pat) => Ok(pat.ident.clone().into()),
+            syn::Pat::Path(pat) => Ok(pat.path.clone()),
+            syn::Pat::TupleStruct(pat) => Ok(pat.path.clone()),
+            syn::Pat::Struct(pat) => Ok(pat.path.clone()),
+            pat => Err(syn::Error::new(
+                pat.span(),
+                format!(
+                    "entry pattern `{}` does not start with a path",
+                    pat.to_token_stream()
+                ),
+            )),
+        }
+    }
+
+    /// Return the identifier naming this entry, i.e. the last segment of its [path](Self::path).
+    pub(crate) fn ident(&self) -> syn::Result<syn::Ident> {
+        let path = self.path()?;
+        path.segments
+            .last()
+            .map(|segment| segment.ident.clone())
+            .ok_or_else(|| syn::Error::new(path.span(), "entry path has no segments"))
+    }
+
+    /// Return the tokens of the associated item with the given name.
+    ///
+    /// Fails if the entry does not have a named associated item called `name`.
+    pub(crate) fn named_associated_tokens(&self, name: &syn::Ident) -> syn::Result<TokenStream2> {
+        self.associated
+            .as_ref()
+            .and_then(|(_, associated)| associated.named(name))
+            .map(ToTokens::to_token_stream)
+            .ok_or_else(|| {
+                syn::Error::new(
+                    self.pat.span(),
+                    format!(
+                        "entry `{}` has no associated item named `{name}`",
+                        self.pat.to_token_stream()
+                    ),
+                )
+            })
+    }
+
+    /// Return the tokens of the positional associated item at the given index.
+    ///
+    /// Returns `None` if the entry does not have a positional associated list.
+    /// Fails if the index is out of range.
+    pub(crate) fn positional_associated_tokens(
+        &self,
+        index: usize,
+    ) -> syn::Result<Option<TokenStream2>> {
+        let Some(positional) = self
+            .associated
+            .as_ref()
+            .and_then(|(_, associated)| associated.positional(index))
+        else {
+            return Ok(None);
+        };
+        positional
+            .map(|item| Some(item.to_token_stream()))
+            .map_err(|len| {
+                syn::Error::new(
+                    self.pat.span(),
+                    format!(
+                        "index {index} is out of range for entry `{}` with {len} associated items",
+                        self.pat.to_token_stream()
+                    ),
+                )
+            })
+    }
+
+    /// Parse the optional `if <guard>` part that follows the pattern and associated tokens.
+    fn parse_guard(input: ParseStream<'_>) -> syn::Result<Option<(Token![if], TokenStream2)>> {
+        if !input.peek(Token![if]) {
+            return Ok(None);
+        }
+        let if_token: Token![if] = input.parse()?;
+        let tokens = DelegateArm::parse_tokens_until(input, |input| {
+            input.peek(Token![,]) || input.peek(Token![=>])
+        })?;
+        debug_trace!("parsed entry guard: {tokens}");
+        Ok(Some((if_token, tokens)))
+    }
+
+    /// Parse the optional `=> <body>` part that overrides the arm body for this entry.
+    fn parse_body(
+        input: ParseStream<'_>,
+        config: &Config,
+    ) -> syn::Result<Option<(Token![=>], TokenStream2)>> {
+        if !input.peek(Token![=>]) {
+            return Ok(None);
+        }
+        let fat_arrow_token: Token![=>] = input.parse()?;
+        let tokens = crate::expr::parse_tokens(input, config.sigil)
+            .wrap_err(input.error("failed to parse entry body tokens"))?;
+        debug_trace!("parsed entry body: {tokens}");
+        Ok(Some((fat_arrow_token, tokens)))
+    }
+
     /// Parse the optional `: <tokens>` part that can accompany a pattern inside the entry list.
-    fn parse_associated(input: ParseStream<'_>) -> syn::Result<Option<(Token![:], Associated)>> {
+    fn parse_associated(
+        input: ParseStream<'_>,
+        config: &Config,
+    ) -> syn::Result<Option<(Token![:], Associated)>> {
         if input.peek(Token![:]) {
             debug_trace!("parsing associated tokens in: {input}");
             let colon_token = input.parse()?;
-            let associated = input.parse()?;
+            let associated = Associated::parse_with(input, config.sigil)?;
             Ok(Some((colon_token, associated)))
         } else {
             Ok(None)
         }
     }
 }
+
+/// Return `true` if the tokens end in a braced group.
+fn ends_with_block(tokens: &TokenStream2) -> bool {
+    matches!(
+        tokens.clone().into_iter().last(),
+        Some(proc_macro2::TokenTree::Group(group)) if group.delimiter() == proc_macro2::Delimiter::Brace
+    )
+}
@@ -0,0 +1,447 @@
+//! Implementation of a single entry group: `path::{ ... } [pat]`.
+
+use proc_macro2::{Delimiter, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens as _};
+use syn::{parse::ParseStream, spanned::Spanned as _, Token};
+
+use crate::{
+    associated::Associated,
+    config::Config,
+    delegate_arm::DelegateArm,
+    delegate_entry::DelegateEntry,
+    expr::peek_invisible_group,
+    substitute::Context,
+    util::{debug_trace, SynErrorContext as _},
+};
+
+/// A group of entries inside the pattern of a delegate arm:
+/// `path::{ Foo[: bar], ... } [pat]`
+#[derive(Clone)]
+pub struct DelegateGroup {
+    pub path: Option<syn::Path>,
+    pub path_sep: Option<Token![::]>,
+    pub brace_token: syn::token::Brace,
+    pub entries: Vec<DelegateEntry>,
+    pub pat: Option<TokenStream2>,
+}
+
+impl DelegateGroup {
+    /// Parse a group whose entries are expanded according to `config`.
+    ///
+    /// The pattern following the entries ends at a top-level `,`, `if` or `=>`.
+    pub fn parse_with(input: ParseStream<'_>, config: &Config) -> syn::Result<Self> {
+        debug_trace!("parsing path");
+        let path = Self::parse_path(input)?;
+        let path_sep = Self::parse_path_sep(input, path.as_ref())?;
+        debug_trace!("parsing entries");
+        let (brace_token, entries) = Self::parse_entries(input, config)?;
+        if config.uniform_assoc {
+            Self::check_uniform_assoc(&entries)?;
+        }
+        let pat = Self::parse_pat(input)?;
+        Ok(Self {
+            path,
+            path_sep,
+            brace_token,
+            entries,
+            pat,
+        })
+    }
+
+    /// Return the index where a group starts in the tokens of a pattern element, if any.
+    ///
+    /// A group is a top-level `{ ... }` at the start of the element, or directly after `::`,
+    /// in which case it starts with the path before the `::`.
+    pub fn find_start(tts: &[TokenTree]) -> Option<usize> {
+        tts.iter().enumerate().find_map(|(i, tt)| {
+            if !matches!(tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace) {
+                return None;
+            }
+            if i == 0 {
+                return Some(0);
+            }
+            let path_sep = i.checked_sub(2)?;
+            is_path_sep(&tts[path_sep..i]).then(|| path_start(tts, path_sep))
+        })
+    }
+
+    /// Parse the optional path (e.g. `::abc::SomeEnum`) before `::{`.
+    ///
+    /// A forwarded `path` fragment arrives as a single invisible group and is taken as a whole.
+    fn parse_path(input: ParseStream<'_>) -> syn::Result<Option<syn::Path>> {
+        if !peek_invisible_group(input) && input.peek(syn::token::Brace) {
+            return Ok(None);
+        }
+        let mut tokens: Vec<TokenTree> = vec![];
+        while !input.is_empty() {
+            if !peek_invisible_group(input)
+                && input.peek(Token![::])
+                && input.peek3(syn::token::Brace)
+            {
+                if tokens.is_empty() {
+                    return Err(syn::Error::new(
+                        input.span(),
+                        "found leading path separator, expected non-crate path",
+                    ));
+                }
+                break;
+            }
+            let tt = input.parse()?;
+            tokens.push(tt);
+        }
+        let ts: TokenStream2 = tokens.into_iter().collect();
+        debug_trace!("parsed path: {ts}");
+        syn::parse2(ts)
+            .map(Some)
+            .wrap_err(input.error("failed to parse delegate arm path"))
+    }
+
+    /// Parse a `::` token if outer path is present.
+    fn parse_path_sep(
+        input: ParseStream<'_>,
+        outer_path: Option<&syn::Path>,
+    ) -> syn::Result<Option<Token![::]>> {
+        if outer_path.is_some() {
+            Ok(Some(input.parse()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parse the `{ ... }` part that contains one or more [`DelegateEntry`] items.
+    fn parse_entries(
+        input: ParseStream<'_>,
+        config: &Config,
+    ) -> syn::Result<(syn::token::Brace, Vec<DelegateEntry>)> {
+        let content;
+        let brace_token = syn::braced!(content in input);
+        let entries = DelegateEntry::parse_multiple(&content, config).wrap_err(syn::Error::new(
+            content.span(),
+            "failed to parse delegate arm entry",
+        ))?;
+        Ok((brace_token, entries))
+    }
+
+    /// Check that all associated items of the entries are of the same kind.
+    ///
+    /// Entries without associated items and items with placeholders are not checked.
+    fn check_uniform_assoc(entries: &[DelegateEntry]) -> syn::Result<()> {
+        let mut kinds = entries.iter().filter_map(|entry| {
+            entry
+                .associated
+                .as_ref()
+                .filter(|(_, associated)| !matches!(associated, Associated::Verbatim(_)))
+                .map(|(_, associated)| (entry, associated))
+        });
+        let Some((first_entry, first)) = kinds.next() else {
+            return Ok(());
+        };
+        for (entry, associated) in kinds {
+            if associated.as_ref() != first.as_ref() {
+                return Err(syn::Error::new(
+                    associated.span(),
+                    format!(
+                        "associated item of entry `{}` is of kind `{}`, but the one of entry `{}` is of kind `{}`",
+                        entry.pat.to_token_stream(),
+                        associated.as_ref(),
+                        first_entry.pat.to_token_stream(),
+                        first.as_ref(),
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse an optional pattern that follows the entry list.
+    fn parse_pat(input: ParseStream<'_>) -> syn::Result<Option<TokenStream2>> {
+        let is_end = |input: ParseStream<'_>| {
+            input.peek(Token![,]) || input.peek(Token![if]) || input.peek(Token![=>])
+        };
+        if input.is_empty() || (!peek_invisible_group(input) && is_end(input)) {
+            return Ok(None);
+        }
+        let tokens = DelegateArm::parse_tokens_until(input, is_end)?;
+        Ok(Some(tokens))
+    }
+
+    /// Combine path, the current entry pattern and pattern into the
+    /// final pattern of the group.
+    pub fn build_pattern_with(&self, cx: &Context<'_>) -> syn::Result<syn::Pat> {
+        // Perform substitution on the arm pattern, if available.
+        let arm_pat_ts = cx.arm_pat_tokens()?;
+        Self::build_entry_pattern(
+            self.path.as_ref(),
+            self.path_sep.as_ref(),
+            cx.entry,
+            arm_pat_ts.as_ref(),
+        )
+    }
+
+    /// Build the final pattern of `entry`, like [`build_final_pattern`](Self::build_final_pattern).
+    ///
+    /// An entry of a nested group is wrapped in the variants named by its parents, e.g.
+    /// `Event::Input(Input::Key(e))` for the entry `Key` of `Event::{ Input::{ Key } }(e)`.
+    pub fn build_entry_pattern(
+        path: Option<&syn::Path>,
+        path_sep: Option<&Token![::]>,
+        entry: &DelegateEntry,
+        arm_pat_ts: Option<&TokenStream2>,
+    ) -> syn::Result<syn::Pat> {
+        let Some((innermost, outer)) = entry.parents.split_last() else {
+            return Self::build_final_pattern(path, path_sep, &entry.pat, arm_pat_ts);
+        };
+        let nested_sep = Token![::](innermost.span());
+        let mut pat =
+            Self::build_final_pattern(Some(innermost), Some(&nested_sep), &entry.pat, arm_pat_ts)?;
+        let mut inner = innermost;
+        for parent in outer.iter().rev() {
+            pat = Self::wrap_pattern(Some(parent), Some(&nested_sep), inner, &pat)?;
+            inner = parent;
+        }
+        Self::wrap_pattern(path, path_sep, inner, &pat)
+    }
+
+    /// Wrap the pattern of a nested group with the variant named by the last segment of its path.
+    fn wrap_pattern(
+        path: Option<&syn::Path>,
+        path_sep: Option<&Token![::]>,
+        nested_path: &syn::Path,
+        pat: &syn::Pat,
+    ) -> syn::Result<syn::Pat> {
+        let variant = nested_path
+            .segments
+            .last()
+            .map(|segment| &segment.ident)
+            .ok_or_else(|| syn::Error::new(nested_path.span(), "nested path has no segments"))?;
+        Ok(syn::Pat::Verbatim(quote!(#path #path_sep #variant(#pat))))
+    }
+
+    /// Build the final pattern of the group.
+    ///
+    /// This function recursively handles or-patterns (`A | B`) by applying the
+    /// path and arm pattern transformation to each alternative, then recombining
+    /// them.
+    /// For example, `Test::{ A, B | C }(case)` produces `Test::B(case) | Test::C(case)`
+    /// as the second generated match arm.
+    pub fn build_final_pattern(
+        path: Option<&syn::Path>,
+        path_sep: Option<&Token![::]>,
+        entry_pat: &syn::Pat,
+        arm_pat_ts: Option<&TokenStream2>,
+    ) -> syn::Result<syn::Pat> {
+        // How a regular match arm pattern is built.
+        let verbatim_join = || syn::Pat::Verbatim(quote!(#path #path_sep #entry_pat #arm_pat_ts));
+        // Build the final pattern.
+        #[allow(
+            clippy::match_same_arms,
+            reason = "loses semantic distinction between cases"
+        )]
+        match (&entry_pat, &arm_pat_ts) {
+            // Or-pattern: recursively transform each alternative and recombine.
+            (syn::Pat::Or(or_pat), _) => Self::build_or_pattern(path, path_sep, or_pat, arm_pat_ts),
+            // Fully compatible.
+            (syn::Pat::Ident(_) | syn::Pat::Path(_), _) => Ok(verbatim_join()),
+            // Only build if no arm pattern is present.
+            (syn::Pat::TupleStruct(_) | syn::Pat::Struct(_), None) => Ok(verbatim_join()),
+            // Incompatible. Error if arm pattern is present.
+            (_, Some(_)) => Err(syn::Error::new(
+                entry_pat.span(),
+                "entry pattern incompatible with arm pattern",
+            )),
+            // No arm pattern, so just use the entry pattern.
+            (_, None) => Ok(entry_pat.clone()),
+        }
+    }
+
+    /// Recursively transform an or-pattern by applying path and arm pattern to each case.
+    fn build_or_pattern(
+        path: Option<&syn::Path>,
+        path_sep: Option<&Token![::]>,
+        or_pat: &syn::PatOr,
+        arm_pat_ts: Option<&TokenStream2>,
+    ) -> syn::Result<syn::Pat> {
+        let cases: syn::punctuated::Punctuated<syn::Pat, Token![|]> = or_pat
+            .cases
+            .iter()
+            .map(|case| Self::build_final_pattern(path, path_sep, case, arm_pat_ts))
+            .collect::<syn::Result<_>>()?;
+        Ok(syn::Pat::Or(syn::PatOr {
+            attrs: or_pat.attrs.clone(),
+            leading_vert: or_pat.leading_vert,
+            cases,
+        }))
+    }
+}
+
+/// Name of the placeholder that repeats the current entry of a group at another position.
+const SAME: &str = "same";
+
+/// The current entry of a group, repeated at another position of the pattern:
+/// `[path::]$same [pat]`, or `[path::]$N.same [pat]` for the group with the one-based number `N`.
+#[derive(Clone)]
+pub struct SameEntry {
+    /// Index of the referenced group in the arm.
+    pub group_index: usize,
+    /// Span of the `same` identifier, for errors about the referenced group.
+    pub span: Span,
+    pub path: Option<syn::Path>,
+    pub path_sep: Option<Token![::]>,
+    pub pat: Option<TokenStream2>,
+}
+
+impl SameEntry {
+    /// Parse the tokens of a pattern element containing a top-level `$same`, or return `None` if
+    /// there is none.
+    ///
+    /// Returns the index where the path before `$same` starts, along with the parsed entry.
+    pub fn parse_from(tts: &[TokenTree], sigil: char) -> syn::Result<Option<(usize, Self)>> {
+        let Some((start, group_index, ident)) = (0..tts.len()).find_map(|i| {
+            match &tts[i..] {
+                [TokenTree::Punct(p), TokenTree::Ident(ident), ..]
+                    if p.as_char() == sigil && ident == SAME =>
+                {
+                    Some((i, 0, ident))
+                }
+                [TokenTree::Punct(p), TokenTree::Literal(number), TokenTree::Punct(dot), TokenTree::Ident(ident), ..]
+                    if p.as_char() == sigil && dot.as_char() == '.' && ident == SAME =>
+                {
+                    let number = number.to_string().parse::<usize>().ok()?;
+                    Some((i, number.checked_sub(1)?, ident))
+                }
+                _ => None,
+            }
+        }) else {
+            return Ok(None);
+        };
+        let span = ident.span();
+        let end = tts[start..]
+            .iter()
+            .position(|tt| matches!(tt, TokenTree::Ident(ident) if ident == SAME))
+            .map_or(tts.len(), |offset| start + offset + 1);
+        let path_start = path_start(tts, start);
+        let (path, path_sep) = Self::parse_prefix(&tts[path_start..start], span, sigil)?;
+        let pat = (end < tts.len()).then(|| tts[end..].iter().cloned().collect());
+        let same = Self {
+            group_index,
+            span,
+            path,
+            path_sep,
+            pat,
+        };
+        Ok(Some((path_start, same)))
+    }
+
+    /// Parse the optional `path::` before the sigil.
+    fn parse_prefix(
+        prefix: &[TokenTree],
+        span: Span,
+        sigil: char,
+    ) -> syn::Result<(Option<syn::Path>, Option<Token![::]>)> {
+        let Some(path_len) = prefix.len().checked_sub(2) else {
+            if prefix.is_empty() {
+                return Ok((None, None));
+            }
+            return Err(Self::prefix_error(span, sigil));
+        };
+        let path_sep = syn::parse2(prefix[path_len..].iter().cloned().collect())
+            .map_err(|_| Self::prefix_error(span, sigil))?;
+        let path = syn::parse2(prefix[..path_len].iter().cloned().collect())
+            .wrap_err_with(|| Self::prefix_error(span, sigil))?;
+        Ok((Some(path), Some(path_sep)))
+    }
+
+    fn prefix_error(span: Span, sigil: char) -> syn::Error {
+        syn::Error::new(
+            span,
+            format!("expected `<path>::` or nothing before `{sigil}{SAME}`"),
+        )
+    }
+
+    /// Build the pattern for the current entry of the referenced group.
+    pub fn build_pattern_with(&self, cx: &Context<'_>) -> syn::Result<syn::Pat> {
+        let pat = self
+            .pat
+            .as_ref()
+            .map(|ts| crate::substitute::substitute(ts, cx))
+            .transpose()?;
+        DelegateGroup::build_entry_pattern(
+            self.path.as_ref(),
+            self.path_sep.as_ref(),
+            cx.entry,
+            pat.as_ref(),
+        )
+    }
+}
+
+/// Return `true` if `tts` is a `::` token.
+fn is_path_sep(tts: &[TokenTree]) -> bool {
+    matches!(
+        tts,
+        [TokenTree::Punct(first), TokenTree::Punct(second)]
+            if first.as_char() == ':'
+                && first.spacing() == proc_macro2::Spacing::Joint
+                && second.as_char() == ':'
+    )
+}
+
+/// Return the index where the path ending right before `end` starts, e.g. at `E` in `&E::`.
+///
+/// The path consists of identifiers, `::` and forwarded `macro_rules!` fragments. Binding mode
+/// keywords are not part of it.
+fn path_start(tts: &[TokenTree], end: usize) -> usize {
+    let mut start = end;
+    loop {
+        if start >= 2 && is_path_sep(&tts[start - 2..start]) {
+            start -= 2;
+            continue;
+        }
+        match start.checked_sub(1).map(|i| &tts[i]) {
+            Some(TokenTree::Ident(ident))
+                if !matches!(ident.to_string().as_str(), "mut" | "ref" | "box") => {}
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {}
+            Some(TokenTree::Punct(_)) if angle_depth_change(tts, start - 1) < 0 => {
+                // Generic arguments, e.g. `<u8, u16>` in `Either::<u8, u16>::`.
+                match generics_start(tts, start) {
+                    Some(open) => {
+                        start = open;
+                        continue;
+                    }
+                    None => return start,
+                }
+            }
+            _ => return start,
+        }
+        start -= 1;
+    }
+}
+
+/// Return the index of the `<` that opens the generic arguments ending with the `>` before `end`.
+fn generics_start(tts: &[TokenTree], end: usize) -> Option<usize> {
+    let mut depth = 0;
+    for i in (0..end).rev() {
+        depth += angle_depth_change(tts, i);
+        if depth == 0 {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Return how the token at index `i` changes the nesting depth of generic arguments.
+///
+/// `<` opens and `>` closes generic arguments, except for the `>` of `->`.
+pub fn angle_depth_change(tts: &[TokenTree], i: usize) -> isize {
+    let TokenTree::Punct(punct) = &tts[i] else {
+        return 0;
+    };
+    let is_arrow = || {
+        matches!(i.checked_sub(1).map(|prev| &tts[prev]), Some(TokenTree::Punct(prev))
+            if prev.as_char() == '-' && prev.spacing() == proc_macro2::Spacing::Joint)
+    };
+    match punct.as_char() {
+        '<' => 1,
+        '>' if !is_arrow() => -1,
+        _ => 0,
+    }
+}
@@ -11,7 +11,7 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{ToTokens, TokenStreamExt as _};
 use syn::{parse::discouraged::Speculative as _, Token};
 
-use crate::delegate_arm::DelegateArm;
+use crate::{config::Config, delegate_arm::DelegateArm};
 
 /// Represents the entire input to the `delegate_match!` procedural macro.
 #[allow(
@@ -48,8 +48,9 @@ impl syn::parse::Parse for ExprDelegateMatch {
         let expr = syn::Expr::parse_without_eager_brace(input)?;
         let content;
         let brace_token = syn::braced!(content in input);
-        let inner_attrs = content.call(syn::Attribute::parse_inner)?;
-        let arms = Arm::parse_all(&content)?;
+        let mut inner_attrs = content.call(syn::Attribute::parse_inner)?;
+        let config = Config::take_from(&mut inner_attrs)?;
+        let arms = Arm::parse_all(&content, &config)?;
         Ok(Self {
             outer_attrs,
             match_token,
@@ -69,24 +70,23 @@ pub enum Arm {
 }
 
 impl Arm {
-    fn parse_all(input: syn::parse::ParseStream<'_>) -> syn::Result<Vec<Self>> {
+    fn parse_all(input: syn::parse::ParseStream<'_>, config: &Config) -> syn::Result<Vec<Self>> {
         let mut arms = Vec::new();
         while !input.is_empty() {
-            arms.push(input.parse()?);
+            arms.push(Self::parse_with(input, config)?);
         }
         Ok(arms)
     }
-}
 
-impl syn::parse::Parse for Arm {
-    fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
+    /// Parse a single arm, using `config` for delegate arms.
+    fn parse_with(input: syn::parse::ParseStream<'_>, config: &Config) -> syn::Result<Self> {
         let fork = input.fork();
         // Try a normal arm first
         let mut delegate_err = None;
         let result = fork.parse::<syn::Arm>().map_or_else(
             |e| {
                 delegate_err = Some(e);
-                input.parse::<DelegateArm>().map(Arm::Delegate)
+                DelegateArm::parse_with(input, config).map(Arm::Delegate)
             },
             |regular| {
                 input.advance_to(&fork);
@@ -1,5 +1,6 @@
 //! Utilities for working with expressions.
 
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use quote::ToTokens as _;
 use syn::{parse::ParseStream, Token};
 
@@ -47,10 +48,13 @@ impl NeedsCommaAsArmBody for syn::Expr {
 /// the parser stops right after that group.
 /// This covers block-like expressions that are commonly written without the trailing comma
 /// in a match arm.
+/// The same applies to invisible groups (forwarded `macro_rules!` fragments) that end in a braced
+/// group, such as an `$e:expr` fragment holding a block or an `if` expression.
 /// In every other situation the whole remaining input is returned (leaving the decision to the caller).
-pub fn parse_tokens(input: ParseStream<'_>) -> syn::Result<proc_macro2::TokenStream> {
-    use proc_macro2::{Delimiter, TokenTree};
-
+///
+/// `sigil` introduces placeholders, so that a braced group followed by `$else` is not mistaken
+/// for the end of the fragment.
+pub fn parse_tokens(input: ParseStream<'_>, sigil: char) -> syn::Result<TokenStream> {
     let mut collected = Vec::<TokenTree>::new();
 
     while !input.is_empty() {
@@ -66,15 +70,21 @@ pub fn parse_tokens(input: ParseStream<'_>) -> syn::Result<proc_macro2::TokenStr
             TokenTree::Group(group) => {
                 let delim = group.delimiter();
                 debug_trace!("parsed group: {tt}");
+                let is_repetition = delim == Delimiter::Parenthesis
+                    && matches!(collected.last(), Some(TokenTree::Punct(p)) if p.as_char() == sigil);
                 collected.push(tt.clone());
-                if matches!(
-                    delim,
-                    Delimiter::Parenthesis | Delimiter::Bracket | Delimiter::Brace
-                ) {
+                if is_repetition {
+                    // `$( ... ) <separator> *`, where the separator may be a comma.
+                    collected.extend(parse_repetition_suffix(input)?);
+                    continue;
+                }
+                let ends_block_like =
+                    delim != Delimiter::None || is_block_like_fragment(&group.stream());
+                if ends_block_like {
                     // Special case.
                     // Block-like expression without a trailing comma inside a match arm.
                     // Look ahead. If the expression does not continue, we finish right here.
-                    if !expr_continues(input) {
+                    if !expr_continues(input) && !peek_else(input, sigil) {
                         break;
                     }
                 }
@@ -91,6 +101,61 @@ pub fn parse_tokens(input: ParseStream<'_>) -> syn::Result<proc_macro2::TokenStr
     Ok(collected.into_iter().collect())
 }
 
+/// Parse the optional separator and the `*` following the group of a `$( ... )` repetition.
+fn parse_repetition_suffix(input: ParseStream<'_>) -> syn::Result<Vec<TokenTree>> {
+    let is_star = |tt: Option<(proc_macro2::Punct, _)>| tt.is_some_and(|(p, _)| p.as_char() == '*');
+    let cursor = input.cursor();
+    let len = if is_star(cursor.punct()) {
+        1
+    } else if cursor
+        .punct()
+        .is_some_and(|(_, next)| is_star(next.punct()))
+    {
+        2
+    } else {
+        0
+    };
+    (0..len).map(|_| input.parse()).collect()
+}
+
+/// Return `true` if the contents of an invisible group end in a braced group.
+fn is_block_like_fragment(stream: &TokenStream) -> bool {
+    match stream.clone().into_iter().last() {
+        Some(TokenTree::Group(group)) => match group.delimiter() {
+            Delimiter::Brace => true,
+            Delimiter::None => is_block_like_fragment(&group.stream()),
+            Delimiter::Parenthesis | Delimiter::Bracket => false,
+        },
+        _ => false,
+    }
+}
+
+/// Return `true` if the next token is an invisible group, e.g. a forwarded `macro_rules!` fragment.
+///
+/// [`ParseStream::peek`] looks through such groups, so this must be checked before peeking at
+/// tokens that would end a fragment, to avoid treating the contents of a group as a boundary.
+pub fn peek_invisible_group(input: ParseStream<'_>) -> bool {
+    input.cursor().group(Delimiter::None).is_some()
+}
+
+/// Return `true` if the next tokens are `$else`, continuing an `$if(...) { ... }` construct.
+/// Any number of `^` scope prefixes may follow the sigil.
+fn peek_else(input: ParseStream<'_>, sigil: char) -> bool {
+    let Some((punct, mut rest)) = input.cursor().punct() else {
+        return false;
+    };
+    if punct.as_char() != sigil {
+        return false;
+    }
+    while let Some((punct, next)) = rest.punct() {
+        if punct.as_char() != '^' {
+            return false;
+        }
+        rest = next;
+    }
+    rest.ident().is_some_and(|(ident, _)| ident == "else")
+}
+
 /// Return `true` when the next token clearly continues the current expression.
 ///
 /// This is a heuristic; it only has to cover the patterns that are realistically going to appear
@@ -98,6 +163,11 @@ pub fn parse_tokens(input: ParseStream<'_>) -> syn::Result<proc_macro2::TokenStr
 ///
 /// (Method calls, field access, `?`, indexing, etc.)
 fn expr_continues(input: ParseStream<'_>) -> bool {
+    // A forwarded fragment never continues an expression, but peeking would look inside it.
+    if peek_invisible_group(input) {
+        return false;
+    }
+
     // Postfix continuation by a single `.` or `?` token.
     if input.peek(Token![.]) || input.peek(Token![?]) {
         return true;
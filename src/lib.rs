@@ -25,8 +25,11 @@
 )]
 
 mod associated;
+mod conditional;
+mod config;
 mod delegate_arm;
 mod delegate_entry;
+mod delegate_group;
 mod delegate_match;
 mod expr;
 mod substitute;
@@ -48,13 +51,19 @@ use syn::parse_macro_input;
 ///
 /// ```text
 /// match <scrutinee_expr> {
-///     [<arm_path>::]{ <entry_pat> [: <assoc_ts>][, ...] } [<arm_pat>] [if <guard_expr>] => <body_expr>[[,] ...]
+///     [<arm_path>::]{ [#[<entry_attr>]] <entry_pat> [: <assoc_ts>] [if <entry_guard>] [=> <entry_body>][, ...] } [<arm_pat>] [if <guard_expr>] => <body_expr>[[,] ...]
 /// }
 /// ```
 ///
 /// - `arm_path` &mdash; optional path prefix (e.g. `MyEnum` or `::std::io`)
+/// - `entry_attr` &mdash; optional outer attributes (e.g. `#[cfg(...)]`) for the entry's generated arm,
+///   placed after the attributes of the delegate arm.
 /// - `entry_pat` &mdash; individual *entry pattern*, also available as the `$entry_pat` placeholder.
 /// - `assoc_ts` &mdash; *associated syntax item*, also available as the `$assoc_ts` placeholder.
+/// - `entry_guard` &mdash; an optional `if` guard for the entry's generated arm only.
+///   If the arm has a guard too, both are combined as `(<guard_expr>) && (<entry_guard>)`.
+/// - `entry_body` &mdash; an optional expression that replaces `body_expr` for the entry's generated arm.
+///   Like a match arm body, a block-like entry body does not need a trailing comma.
 /// - `arm_pat` &mdash; an optional pattern appended to every entry.
 /// - `guard_expr` &mdash; an optional `if` guard.
 /// - `body_expr` &mdash; expression generated for each entry.
@@ -67,21 +76,191 @@ use syn::parse_macro_input;
 /// }
 /// ```
 ///
-/// Two placeholders are substituted for every entry *before the code is
+/// Placeholders are substituted for every entry *before the code is
 /// type-checked*, and they may appear in the following places:
+///   - inside the outer attributes of the delegate arm or an entry, e.g. `#[cfg(feature = $assoc_ts)]`,
 ///   - inside the delegate arm pattern `arm_pat` (if present),
-///   - inside the match arm guard expression `guard_expr` (if present),
-///   - inside the arm body expression `body_expr`.
+///   - inside the match arm guard expression `guard_expr` and entry guards (if present),
+///   - inside the arm body expression `body_expr` and entry bodies.
+///
+/// Placeholders may also appear inside associated tokens, where they refer to the owning entry.
+/// Associated tokens are substituted before they are inserted, so they don't have to be valid
+/// syntax until then. An associated item that refers to itself, directly or through
+/// other associated items, is an error.
 ///
 /// The available placeholders are:
 ///   - `$entry_pat` &mdash; the entry pattern for a generated arm.
+///   - `$entry_pat.parent` &mdash; the path of the [nested group](#nested-entries) containing the entry.
+///     Using it for an entry outside of a nested group is an error.
 ///   - `$assoc_ts` &mdash; the tokens following an entry, up until the next one (excluding the colon).
 ///     Can be any of the following:
-///       - Expression
-///       - Statement
+///       - Expression (including `let` expressions)
 ///       - Pattern
 ///       - Type
 ///     The first that matches is used, in the above order.
+///     A kind prefix forces a specific kind instead: `expr <expr>`, `pat <pat>`, `type <type>`
+///     or `item <item>`, e.g. `type HashMap<K, V>` or `item fn helper() {}`.
+///     A lone `expr`, `pat`, `type` or `item` is not a prefix. Neither are `expr`, `pat` and
+///     `item` if the whole item is a valid expression, e.g. `item.len()`.
+///     Items are only available with a prefix.
+///     Using it for an entry without associated tokens is an error.
+///   - `$assoc_ts(or: <default>)` &mdash; like `$assoc_ts`, but expands to `<default>` for entries
+///     without associated tokens. The default may be empty, e.g. `$assoc_ts(or:)`.
+///   - `$assoc.<name>` &mdash; a named associated item, for entries written as
+///     `<entry_pat>: { <name> = <item>, ... }`. Each item is classified like `$assoc_ts`.
+///     Using a name that is missing on some entry of the arm is an error.
+///   - `$assoc_ts.<index>` &mdash; a positional associated item, for entries written as
+///     `<entry_pat>: (<item>, ...)`. Each item is classified like `$assoc_ts`.
+///     An out-of-range index is an error. For other entries, `.<index>` is left as a field access.
+///   - `$entry_index` &mdash; the zero-based index of the entry inside its group.
+///   - `$entry_count` &mdash; the number of entries in the group.
+///   - `$is_first`, `$is_last` &mdash; `true` for the first or last entry of the group, `false` otherwise.
+///   - `$arm_path` &mdash; the `arm_path` prefix. Using it in an arm without a prefix is an error.
+///   - `$arm_pat` &mdash; the `arm_pat` tokens, or nothing if absent. Not available inside `arm_pat`.
+///   - `$entry_ident` &mdash; the identifier naming the entry, e.g. `A` for the entry pattern `A(x)`.
+///   - `$entry_path` &mdash; the full path of the entry, e.g. `MyEnum::A` for the entry pattern `A(x)`.
+///     Usable as an expression or constructor. For an entry of a nested group, the path of that
+///     group is used instead, e.g. `Input::Key`.
+///   - `$ident(<fragment>, ...)` &mdash; an identifier built by concatenating the fragments,
+///     e.g. `$ident(handle_, $entry_ident)` expands to `handle_A` for the entry `A`.
+///     Fragments are identifiers, string or integer literals, and placeholders expanding to those.
+///     The identifier is spanned at the entry, so errors such as a missing function point to it.
+///
+/// Placeholders can be followed by a modifier, e.g. `$entry_ident:snake`:
+///   - `:str` &mdash; a string literal of the token text, e.g. `"HttpGzip"`.
+///   - `:snake`, `:SCREAMING`, `:camel`, `:pascal` &mdash; an identifier in `snake_case`,
+///     `SCREAMING_SNAKE_CASE`, `camelCase` or `PascalCase`, e.g. `http_gzip` or `HTTP_GZIP`.
+///   - `:kebab` &mdash; a string literal in `kebab-case`, e.g. `"http-gzip"`.
+///
+/// Case conversions require the placeholder to expand to a single identifier.
+/// Inside interpolated string literals, modifiers that produce a string insert its contents.
+///
+/// ## Groups inside patterns
+///
+/// A group may also appear anywhere inside the arm pattern, e.g. `Some(E::{ A, B }(x))`,
+/// `&E::{ A, B }`, `[E::{ A, B }, ..]` or `S { field: E::{ A, B }(x) }`. The group and its
+/// `arm_pat` extend up to the next `,` or the end of the enclosing delimiters, and are replaced in
+/// place for every entry. Or-pattern entries are parenthesized there, e.g. `&(E::B | E::C)`.
+///
+/// ## Nested entries
+///
+/// An entry may be a nested group itself, for layered enums. Its entries are wrapped in the
+/// variant named by the last segment of its path, e.g.
+/// `Event::{ Input::{ Key, Mouse }, Quit }(e)` expands to `Event::Input(Input::Key(e))`,
+/// `Event::Input(Input::Mouse(e))` and `Event::Quit(e)`. Nested groups may be nested further.
+/// They are flattened in source order, so `$entry_index` and `$entry_count` count all of their
+/// entries. Attributes of a nested group apply to each of its entries.
+///
+/// ## Multiple groups
+///
+/// An arm pattern may contain several groups, e.g.
+/// `(Fmt::{ Json, Cbor }(f), Codec::{ Gzip, Zstd }(c), _)`. This expands to one arm for every
+/// combination of entries, in source order with the last group varying fastest.
+/// Each group may have its own `arm_path` and `arm_pat`.
+/// With the `zip` option, the i-th entries of all groups are paired into one arm instead, e.g.
+/// `(Src::{ A, B }(x), Dst::{ X, Y }(y))` expands to `(Src::A(x), Dst::X(y))` and
+/// `(Src::B(x), Dst::Y(y))`. Zipping groups of different lengths is an error.
+///
+/// A pattern element written as `[<path>::]$same [<pat>]` repeats the current entry of the first
+/// group at that position, e.g. `(E::{ A, B }(x), E::$same(y))` expands to `(E::A(x), E::A(y))`
+/// and `(E::B(x), E::B(y))`. Write `$N.same` to repeat the entry of another group.
+/// This is useful for binary operations on enums, together with the `mismatch` option for the
+/// remaining pairs.
+///
+/// Placeholders refer to the first group. Prefix a placeholder with the one-based number of a
+/// group to refer to that group instead, e.g. `$2.entry_pat` or `$2.assoc.<name>`.
+/// `$( ... )` repetitions and conditionals always use the first group.
+///
+/// ```rust
+/// use delegate_match::delegate_match;
+///
+/// enum Fmt { Json, Cbor }
+/// enum Codec { Gzip, Zstd }
+///
+/// fn extension(fmt: Fmt, codec: Codec) -> &'static str {
+///     delegate_match! {
+///         match (fmt, codec) {
+///             (Fmt::{ Json: "json", Cbor: "cbor" }, Codec::{ Gzip: "gz", Zstd: "zst" }) => {
+///                 concat!($1.assoc_ts, ".", $2.assoc_ts)
+///             }
+///         }
+///     }
+/// }
+///
+/// assert_eq!(extension(Fmt::Cbor, Codec::Gzip), "cbor.gz");
+/// ```
+///
+/// ## Repetition
+///
+/// `$( ... ) <separator> *` expands its contents once per entry of the group, with the
+/// placeholders of that entry, similar to `macro_rules!`. The separator is an optional
+/// punctuation token, e.g. `,` or `|`. For example, `[$( $assoc_ts ),*]` produces an array of all
+/// associated values in every generated arm. Write `$$( ... )` for a literal `$( ... )`, e.g. in
+/// a `macro_rules!` definition inside the body. With a custom sigil, a group that is not followed
+/// by `*` is left as is, e.g. `n @ (1 | 2)` with the sigil `@`.
+///
+/// ## Conditionals
+///
+/// `$if(<condition>) { ... } $else if(<condition>) { ... } $else { ... }` is resolved for every
+/// entry during substitution, wherever placeholders are allowed. Only the contents of the chosen
+/// branch are kept, so the other branches never have to type-check for that entry.
+/// The braces only delimit a branch, add another pair to produce a block.
+/// Both `$else` branches are optional. The available conditions are:
+///   - `entry = A | B`, `entry != A | B` &mdash; compare `$entry_ident` with the given identifiers.
+///   - `index = 0 | 1`, `index != 0 | 1` &mdash; compare `$entry_index` with the given indices.
+///   - `is_first`, `is_last` &mdash; optionally negated with `!`.
+///
+/// ```rust
+/// use delegate_match::delegate_match;
+///
+/// enum Codec { Gzip(u32), Zstd(i32) }
+///
+/// fn level(codec: Codec) -> i64 {
+///     delegate_match! {
+///         match codec {
+///             Codec::{ Gzip, Zstd }(level) => {
+///                 $if(entry = Gzip) { i64::from(level.clamp(1, 9)) } $else { i64::from(level) }
+///             }
+///         }
+///     }
+/// }
+///
+/// assert_eq!(level(Codec::Gzip(20)), 9);
+/// assert_eq!(level(Codec::Zstd(-3)), -3);
+/// ```
+///
+/// ## Configuration
+///
+/// Options can be set with `#![delegate(...)]` inner attributes inside the `match` braces, or for
+/// a single delegate arm with `#[delegate(...)]` outer attributes:
+///   - `interpolate_strings` &mdash; also substitute placeholders inside string literals
+///     (including format strings) with their stringified tokens, as if by `stringify!`.
+///     Inserted text is not escaped.
+///   - `uniform_assoc` &mdash; require all associated items of a delegate arm to be of the same kind,
+///     e.g. all types. Items containing placeholders are not checked.
+///   - `sigil = "<char>"` &mdash; use another character to introduce placeholders, e.g. `@entry_pat`
+///     with `sigil = "@"`. Supported sigils are `$` (default), `#`, `%`, `@` and `~`.
+///     This avoids clashes with metavariables when `delegate_match!` is used inside `macro_rules!`.
+///   - `max_arms = <n>` &mdash; the maximum number of arms a delegate arm with
+///     [multiple groups](#multiple-groups) may expand to, 1024 by default. Exceeding it is an
+///     error, which guards against accidentally large combinations. Arms with a single group are
+///     not limited.
+///   - `zip` &mdash; pair the i-th entries of [multiple groups](#multiple-groups), instead of
+///     expanding to every combination.
+///   - `mismatch = <expr>` &mdash; generate a `_ => <expr>` arm after the arms of a delegate arm,
+///     e.g. for the pairs of different entries left over by `$same`.
+///
+/// A doubled sigil (e.g. `$$`) is an escape that expands to a single literal sigil.
+/// With the default `$` sigil, an identifier after the sigil that is not a known placeholder
+/// (e.g. a typo like `$asoc_ts`) is an error; write `$$ident` for a literal `$ident`,
+/// such as a metavariable of a `macro_rules!` defined in the body.
+/// Unknown placeholders inside interpolated string literals are kept as they are.
+///
+/// ## Nested invocations
+///
+/// Placeholders inside a nested `delegate_match!` belong to the innermost invocation.
+/// Prefix a placeholder with one `^` per level to refer to an enclosing invocation,
+/// e.g. `$^entry_pat` or `$^assoc.<name>`. Escapes inside a nested invocation are left to it.
 ///
 /// The macro is supposed to accept standard Rust `match` expression syntax, extended with the above.
 /// Any other deviation should generally be considered a bug.
@@ -94,6 +273,8 @@ use syn::parse_macro_input;
 /// - This macro performs generation before type-checking is done, so
 ///   the generated code is capable of working with different types, if constructed appropriately.
 /// - The order of generated arms is the order of entries in the source code.
+/// - Any part of the input may come from `macro_rules!` fragments (`$e:expr`, `$p:pat`, `$t:ty`...)
+///   forwarded by a wrapper macro. Use a custom sigil to write placeholders in such macros.
 ///
 /// ## Examples
 ///
@@ -107,6 +288,8 @@ use syn::parse_macro_input;
 ///
 /// delegate_match! {
 ///     match ev {
+///         // Opt into placeholder substitution inside string literals.
+///         #![delegate(interpolate_strings)]
 ///         // This expands to two individual arms.
 ///         MouseEvent::{ Scroll, Position }(x, y) => {
 ///             println!("mouse event: $entry_pat -> ({x}, {y})")
@@ -132,6 +315,22 @@ use syn::parse_macro_input;
 /// }
 /// ```
 ///
+/// ### Using named associated items
+///
+/// ```rust
+/// # use delegate_match::delegate_match;
+/// # enum Msg { Ping, Log }
+/// # let msg = Msg::Ping;
+/// delegate_match! {
+///     match msg {
+///         Msg::{ Ping: { icon = "🏓", code = 1u8 }, Log: { icon = "📝", code = 2u8 } } => {
+///             // Outputs "🏓 1" or "📝 2" depending on the variant.
+///             println!("{} {}", $assoc.icon, $assoc.code)
+///         }
+///     }
+/// }
+/// ```
+///
 /// ### Adding an if guard to multiple entries
 ///
 /// ```rust
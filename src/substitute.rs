@@ -1,59 +1,934 @@
 //! Raw token-stream substitution.
 
-use proc_macro2::{TokenStream as TokenStream2, TokenTree};
-use quote::quote;
+use std::cmp::Ordering;
 
-use crate::util::debug_trace;
+use proc_macro2::{Delimiter, Ident, Literal, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{ext::IdentExt as _, spanned::Spanned as _};
+
+use crate::{
+    delegate_arm::DelegateArm,
+    delegate_entry::DelegateEntry,
+    delegate_group::DelegateGroup,
+    util::{case, debug_trace},
+};
 
-const OPERATOR: char = '$';
 const ENTRY_PAT: &str = "entry_pat";
 const ASSOC_TS: &str = "assoc_ts";
+const ASSOC: &str = "assoc";
+const ENTRY_INDEX: &str = "entry_index";
+const ENTRY_COUNT: &str = "entry_count";
+const IS_FIRST: &str = "is_first";
+const IS_LAST: &str = "is_last";
+const ARM_PATH: &str = "arm_path";
+const ARM_PAT: &str = "arm_pat";
+const ENTRY_IDENT: &str = "entry_ident";
+const ENTRY_PATH: &str = "entry_path";
+const IDENT: &str = "ident";
+
+const PARENT: &str = "parent";
+
+const STR: &str = "str";
+const SNAKE: &str = "snake";
+const KEBAB: &str = "kebab";
+const CAMEL: &str = "camel";
+const PASCAL: &str = "pascal";
+const SCREAMING: &str = "SCREAMING";
+
+/// All modifiers that may follow a placeholder, e.g. `$entry_ident:snake`.
+const MODIFIERS: &[&str] = &[STR, SNAKE, KEBAB, CAMEL, PASCAL, SCREAMING];
+
+/// All known placeholder names, in the order they are listed in diagnostics.
+const PLACEHOLDERS: &[&str] = &[
+    ENTRY_PAT,
+    ASSOC_TS,
+    ASSOC,
+    ENTRY_INDEX,
+    ENTRY_COUNT,
+    IS_FIRST,
+    IS_LAST,
+    ARM_PATH,
+    ARM_PAT,
+    ENTRY_IDENT,
+    ENTRY_PATH,
+    IDENT,
+];
+
+/// Sigil for which unknown placeholders are reported as errors.
+///
+/// Other sigils are also valid Rust operators, so they may legitimately precede an identifier.
+const STRICT_SIGIL: char = '$';
+
+/// The entry that placeholders are substituted for, along with its position in the group.
+///
+/// Placeholders refer to the first group of the arm, unless prefixed with the number of another
+/// group, e.g. `$2.entry_pat`.
+#[derive(Clone, Copy)]
+pub struct Context<'a> {
+    pub arm: &'a DelegateArm,
+    pub group: &'a DelegateGroup,
+    pub entry: &'a DelegateEntry,
+    pub index: usize,
+    pub count: usize,
+    /// Index of `group` in the arm.
+    group_index: usize,
+    /// Index of the current entry of every group in the arm.
+    combination: &'a [usize],
+    /// Whether the arm pattern itself is being substituted.
+    in_arm_pat: bool,
+    /// Associated items that are currently being substituted, innermost first.
+    expanding: Option<&'a Expansion<'a>>,
+}
+
+/// A link in the chain of associated items that are being substituted, used to detect cycles.
+struct Expansion<'a> {
+    /// Placeholder text without the sigil, e.g. `assoc_ts` or `assoc.name`.
+    key: String,
+    /// Index of the group and the entry that own the associated item.
+    group_index: usize,
+    index: usize,
+    parent: Option<&'a Self>,
+}
+
+impl<'a> Context<'a> {
+    /// Create a context for the given entry index of every group, referring to the first group.
+    pub fn new(arm: &'a DelegateArm, combination: &'a [usize]) -> Self {
+        let group = &arm.groups[0];
+        Self {
+            arm,
+            group,
+            entry: &group.entries[combination[0]],
+            index: combination[0],
+            count: group.entries.len(),
+            group_index: 0,
+            combination,
+            in_arm_pat: false,
+            expanding: None,
+        }
+    }
+
+    /// Refer to the group with the given index instead.
+    pub fn with_group(&self, group_index: usize) -> Self {
+        let group = &self.arm.groups[group_index];
+        let index = self.combination[group_index];
+        Self {
+            group,
+            entry: &group.entries[index],
+            index,
+            count: group.entries.len(),
+            group_index,
+            ..*self
+        }
+    }
+
+    /// Refer to the group with the one-based `number` following the sigil, e.g. `$2.entry_pat`.
+    fn with_group_number(&self, number: &Literal) -> syn::Result<Self> {
+        let groups = self.arm.groups.len();
+        match number.to_string().parse::<usize>() {
+            Ok(n) if (1..=groups).contains(&n) => Ok(self.with_group(n - 1)),
+            _ => Err(syn::Error::new(
+                number.span(),
+                format!(
+                    "`{}{number}.` does not refer to a group of this arm, expected a number from 1 to {groups}",
+                    self.arm.config.sigil
+                ),
+            )),
+        }
+    }
+
+    pub const fn is_first(&self) -> bool {
+        self.index == 0
+    }
+
+    pub const fn is_last(&self) -> bool {
+        self.index + 1 == self.count
+    }
+
+    /// Substitute placeholders in the arm pattern, if present.
+    pub fn arm_pat_tokens(&self) -> syn::Result<Option<TokenStream2>> {
+        let cx = Self {
+            in_arm_pat: true,
+            ..*self
+        };
+        self.group
+            .pat
+            .as_ref()
+            .map(|ts| substitute(ts, &cx))
+            .transpose()
+    }
+
+    /// Return the path of the nested group that the entry is part of, for `$entry_pat.parent`.
+    fn parent_tokens(&self, accessor: &Ident) -> syn::Result<TokenStream2> {
+        self.entry
+            .parents
+            .last()
+            .map(ToTokens::to_token_stream)
+            .ok_or_else(|| {
+                syn::Error::new(
+                    accessor.span(),
+                    format!(
+                        "entry `{}` is not part of a nested group",
+                        self.entry.pat.to_token_stream()
+                    ),
+                )
+            })
+    }
+
+    /// Return the full path of the entry, including the arm path prefix.
+    ///
+    /// For an entry of a nested group, the path of that group is used instead, e.g. `Input::Key`.
+    fn entry_path_tokens(&self) -> syn::Result<TokenStream2> {
+        if let Some(parent) = self.entry.parents.last() {
+            let entry_path = self.entry.path()?;
+            return Ok(quote!(#parent::#entry_path));
+        }
+        let arm_path = self.group.path.as_ref();
+        let path_sep = self.group.path_sep.as_ref();
+        let entry_path = self.entry.path()?;
+        Ok(quote!(#arm_path #path_sep #entry_path))
+    }
+
+    /// Resolve the placeholder at the start of `tts`, which directly follows the sigil.
+    ///
+    /// Returns the replacement tokens and the number of consumed token trees,
+    /// or `None` if `tts` does not start with a known placeholder.
+    fn resolve(
+        &self,
+        tts: &[TokenTree],
+        depth: usize,
+    ) -> syn::Result<Option<(TokenStream2, usize)>> {
+        if let Some(number) = group_access(tts) {
+            let cx = self.with_group_number(number)?;
+            return Ok(cx
+                .resolve(&tts[2..], depth)?
+                .map(|(tokens, consumed)| (tokens, consumed + 2)));
+        }
+        let entry = self.entry;
+        // Look ahead at the identifier following the sigil (if any).
+        let Some(TokenTree::Ident(ident)) = tts.first() else {
+            return Ok(None);
+        };
+        let ident_name = ident.to_string();
+        debug_trace!("found placeholder: ${}", ident_name);
+        let replacement = match ident_name.as_str() {
+            ENTRY_PAT => match named_access(&tts[1..]) {
+                Some(name) if name == PARENT => Some((self.parent_tokens(name)?, 3)),
+                _ => Some((entry.pat.to_token_stream(), 1)),
+            },
+            ENTRY_INDEX => Some((usize_literal(self.index, ident), 1)),
+            ENTRY_COUNT => Some((usize_literal(self.count, ident), 1)),
+            IS_FIRST => Some((bool_literal(self.is_first(), ident), 1)),
+            IS_LAST => Some((bool_literal(self.is_last(), ident), 1)),
+            ARM_PATH | ARM_PAT | ENTRY_IDENT | ENTRY_PATH => Some((self.structural(ident)?, 1)),
+            IDENT => Some((self.concat_ident(ident, tts.get(1), depth)?, 2)),
+            ASSOC_TS => {
+                let index = index_access(&tts[1..]);
+                let positional = index
+                    .map(|index| entry.positional_associated_tokens(index))
+                    .transpose()?
+                    .flatten();
+                match (positional, default_access(&tts[1..])) {
+                    (Some(ts), _) => {
+                        let key = format!("{ASSOC_TS}.{}", index.unwrap_or_default());
+                        Some((self.expand_associated(key, &ts, ident)?, 3))
+                    }
+                    (None, Some(default)) => {
+                        let ts = match entry.associated_tokens() {
+                            Some(ts) => self.expand_associated(ASSOC_TS.into(), &ts, ident)?,
+                            None => substitute_at(&default, self, depth)?,
+                        };
+                        Some((ts, 2))
+                    }
+                    (None, None) => {
+                        let ts = entry.required_associated_tokens(self.arm.config.sigil)?;
+                        Some((self.expand_associated(ASSOC_TS.into(), &ts, ident)?, 1))
+                    }
+                }
+            }
+            ASSOC => named_access(&tts[1..])
+                .map(|name| {
+                    let ts = entry.named_associated_tokens(name)?;
+                    let key = format!("{ASSOC}.{name}");
+                    self.expand_associated(key, &ts, ident).map(|ts| (ts, 3))
+                })
+                .transpose()?,
+            // Unexpected identifier after the sigil.
+            _ => None,
+        };
+        let Some((tokens, consumed)) = replacement else {
+            return Ok(None);
+        };
+        match modifier_access(&tts[consumed..]) {
+            Some(modifier) => Ok(Some((apply_modifier(&tokens, modifier)?, consumed + 2))),
+            None => Ok(Some((tokens, consumed))),
+        }
+    }
+
+    /// Substitute placeholders inside associated tokens, which refer to the same entry.
+    ///
+    /// Fails if the associated item refers to itself, directly or indirectly.
+    fn expand_associated(
+        &self,
+        key: String,
+        tokens: &TokenStream2,
+        placeholder: &Ident,
+    ) -> syn::Result<TokenStream2> {
+        let mut link = self.expanding;
+        while let Some(expansion) = link {
+            if expansion.key == key
+                && expansion.group_index == self.group_index
+                && expansion.index == self.index
+            {
+                return Err(syn::Error::new(
+                    placeholder.span(),
+                    format!(
+                        "cyclic placeholder `{}{key}` in associated tokens of entry `{}`",
+                        self.arm.config.sigil,
+                        self.entry.pat.to_token_stream()
+                    ),
+                ));
+            }
+            link = expansion.parent;
+        }
+        let expansion = Expansion {
+            key,
+            group_index: self.group_index,
+            index: self.index,
+            parent: self.expanding,
+        };
+        let cx = Context {
+            expanding: Some(&expansion),
+            ..*self
+        };
+        substitute(tokens, &cx)
+    }
+
+    /// Build an identifier from the fragments in the group following `$ident`,
+    /// e.g. `$ident(handle_, $entry_ident)`.
+    ///
+    /// The identifier is spanned at the entry, so that errors point to it.
+    fn concat_ident(
+        &self,
+        placeholder: &Ident,
+        group: Option<&TokenTree>,
+        depth: usize,
+    ) -> syn::Result<TokenStream2> {
+        let Some(TokenTree::Group(group)) = group.filter(
+            |tt| matches!(tt, TokenTree::Group(g) if g.delimiter() == Delimiter::Parenthesis),
+        ) else {
+            return Err(syn::Error::new(
+                placeholder.span(),
+                format!(
+                    "expected `({IDENT}_fragment, ...)` after `{}{IDENT}`",
+                    self.arm.config.sigil
+                ),
+            ));
+        };
+        let fragments = substitute_at(&group.stream(), self, depth)?;
+        let mut name = String::new();
+        for tt in fragments {
+            match tt {
+                TokenTree::Punct(p) if p.as_char() == ',' => {}
+                TokenTree::Ident(ident) => name.push_str(&ident.unraw().to_string()),
+                TokenTree::Literal(literal) => match syn::Lit::new(literal.clone()) {
+                    syn::Lit::Str(lit) => name.push_str(&lit.value()),
+                    syn::Lit::Int(lit) if lit.suffix().is_empty() => {
+                        name.push_str(lit.base10_digits());
+                    }
+                    _ => return Err(fragment_error(&literal.into())),
+                },
+                tt => return Err(fragment_error(&tt)),
+            }
+        }
+        let mut ident: Ident = syn::parse_str(&name).map_err(|_| {
+            syn::Error::new(group.span(), format!("`{name}` is not a valid identifier"))
+        })?;
+        ident.set_span(self.entry.pat.span());
+        Ok(ident.into_token_stream())
+    }
+
+    /// Resolve a structural placeholder that describes the arm or the entry.
+    fn structural(&self, placeholder: &Ident) -> syn::Result<TokenStream2> {
+        let sigil = self.arm.config.sigil;
+        match placeholder.to_string().as_str() {
+            ARM_PATH => self
+                .group
+                .path
+                .as_ref()
+                .map(ToTokens::to_token_stream)
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        placeholder.span(),
+                        format!("`{sigil}arm_path` used in an arm without a path"),
+                    )
+                }),
+            ARM_PAT if self.in_arm_pat => Err(syn::Error::new(
+                placeholder.span(),
+                format!("`{sigil}arm_pat` cannot be used inside the arm pattern"),
+            )),
+            ARM_PAT => Ok(self.arm_pat_tokens()?.unwrap_or_default()),
+            ENTRY_IDENT => Ok(self.entry.ident()?.into_token_stream()),
+            ENTRY_PATH => self.entry_path_tokens(),
+            _ => unreachable!("not a structural placeholder: {placeholder}"),
+        }
+    }
+}
 
 /// Substitute placeholders with concrete tokens.
-pub fn substitute(
-    tokens: &TokenStream2,
-    entry_pat: &syn::Pat,
-    assoc_ts: Option<&TokenStream2>,
-) -> TokenStream2 {
+pub fn substitute(tokens: &TokenStream2, cx: &Context<'_>) -> syn::Result<TokenStream2> {
     debug_trace!("substitution pass");
-    let mut out = Vec::new();
-    let mut iter = tokens.clone().into_iter().peekable();
-
-    while let Some(tt) = iter.next() {
-        match tt {
-            TokenTree::Punct(ref punct) if punct.as_char() == OPERATOR => {
-                // Look ahead at the identifier following `$` (if any).
-                if let Some(TokenTree::Ident(ident)) = iter.peek() {
-                    let ident_name = ident.to_string();
-                    debug_trace!("found placeholder: ${}", ident_name);
-                    let replacement = match ident_name.as_str() {
-                        ENTRY_PAT => quote!(#entry_pat),
-                        ASSOC_TS => quote!(#assoc_ts),
-                        _ => {
-                            // Unexpected identifier after `$`.
-                            // Leave the `$` in place and fall back to default handling below.
-                            out.push(TokenTree::Punct(punct.clone()));
+    let out = substitute_at(tokens, cx, 0)?;
+    debug_trace!("substitution result: {}", out);
+    Ok(out)
+}
+
+/// Substitute placeholders inside `depth` nested `delegate_match!` invocations.
+///
+/// Placeholders inside a nested invocation belong to that invocation, unless they are prefixed
+/// with one `^` per level of nesting. For example, `$^entry_pat` inside a nested invocation
+/// refers to the entry of the enclosing one.
+fn substitute_at(
+    tokens: &TokenStream2,
+    cx: &Context<'_>,
+    depth: usize,
+) -> syn::Result<TokenStream2> {
+    let tts: Vec<TokenTree> = tokens.clone().into_iter().collect();
+    let mut out = TokenStream2::new();
+    let mut i = 0;
+
+    let sigil = cx.arm.config.sigil;
+    while i < tts.len() {
+        match &tts[i] {
+            TokenTree::Punct(punct) if punct.as_char() == sigil => {
+                let levels = scope_levels(&tts[i + 1..]);
+                let rest = &tts[i + 1 + levels..];
+                match levels.cmp(&depth) {
+                    Ordering::Equal => {
+                        match rest.first() {
+                            Some(TokenTree::Ident(ident)) if ident == "if" => {
+                                let (tokens, consumed) = conditional(rest, cx, depth, levels)?;
+                                out.extend(tokens);
+                                i += 1 + levels + consumed;
+                                continue;
+                            }
+                            Some(TokenTree::Group(group))
+                                if group.delimiter() == Delimiter::Parenthesis =>
+                            {
+                                if let Some((tokens, consumed)) = repetition(rest, cx, depth)? {
+                                    out.extend(tokens);
+                                    i += 1 + levels + consumed;
+                                    continue;
+                                }
+                            }
+                            Some(TokenTree::Ident(ident)) if ident == "else" => {
+                                return Err(syn::Error::new(
+                                    ident.span(),
+                                    format!("`{sigil}else` without a preceding `{sigil}if`"),
+                                ));
+                            }
+                            _ => {}
+                        }
+                        // A doubled sigil is an escape for a single literal sigil.
+                        // Escapes inside nested invocations are left for them to handle.
+                        if let Some(TokenTree::Punct(escaped)) = rest.first() {
+                            if depth == 0 && escaped.as_char() == sigil {
+                                out.extend([rest[0].clone()]);
+                                i += 2;
+                                continue;
+                            }
+                        }
+                        if let Some((replacement, consumed)) = cx.resolve(rest, depth)? {
+                            out.extend(replacement);
+                            i += 1 + levels + consumed;
                             continue;
                         }
-                    };
-                    // Consume the identifier we just peeked at.
-                    iter.next();
-                    out.extend(replacement);
-                    continue;
+                        if let Some(TokenTree::Ident(ident)) = placeholder_ident(rest) {
+                            if sigil == STRICT_SIGIL {
+                                return Err(unknown_error(sigil, levels, ident));
+                            }
+                        }
+                    }
+                    Ordering::Greater => {
+                        if let Some(TokenTree::Ident(ident)) = rest.first() {
+                            return Err(unscoped_error(sigil, levels, ident));
+                        }
+                    }
+                    Ordering::Less => {}
                 }
-                // `$` not followed by ident. Treat it as a normal punctuation token.
-                out.push(punct.clone().into());
+                // Not a placeholder of this invocation. Treat it as a normal punctuation token.
+                out.extend([tts[i].clone()]);
+            }
+            TokenTree::Ident(ident) if is_nested_invocation(ident, &tts[i + 1..]) => {
+                // `delegate_match ! { ... }`
+                out.extend(tts[i..=i + 1].iter().cloned());
+                let TokenTree::Group(group) = &tts[i + 2] else {
+                    unreachable!("nested invocation without a group");
+                };
+                out.extend([substitute_group(group, cx, depth + 1)?]);
+                i += 3;
+                continue;
             }
             TokenTree::Group(group) => {
-                let inner = substitute(&group.stream(), entry_pat, assoc_ts);
-                let new_group = proc_macro2::Group::new(group.delimiter(), inner);
-                out.push(new_group.into());
+                out.extend([substitute_group(group, cx, depth)?]);
+            }
+            TokenTree::Literal(literal) if cx.arm.config.interpolate_strings => {
+                out.extend([interpolate(literal, cx, depth)?]);
+            }
+            other => out.extend([other.clone()]),
+        }
+        i += 1;
+    }
+
+    Ok(out)
+}
+
+/// Expand a `if(<condition>) { ... }` construct following the sigil, with optional
+/// `else if(<condition>) { ... }` and `else { ... }` branches, each introduced by the sigil.
+///
+/// Only the contents of the chosen branch are substituted and returned, along with the number of
+/// consumed token trees.
+fn conditional(
+    tts: &[TokenTree],
+    cx: &Context<'_>,
+    depth: usize,
+    levels: usize,
+) -> syn::Result<(TokenStream2, usize)> {
+    let sigil = cx.arm.config.sigil;
+    let Some((condition, body)) = condition_and_body(&tts[1..]) else {
+        return Err(syn::Error::new(
+            tts[0].span(),
+            format!("expected `(<condition>) {{ ... }}` after `{sigil}if`"),
+        ));
+    };
+    let mut chosen = crate::conditional::eval(condition, cx)?.then_some(body);
+    let mut consumed = 3;
+    // `$else if(...) { ... }` and `$else { ... }`.
+    while let Some(else_token) = else_access(&tts[consumed..], sigil, levels) {
+        consumed += 2 + levels;
+        let rest = &tts[consumed..];
+        match rest.first() {
+            Some(TokenTree::Ident(ident)) if ident == "if" => {
+                let Some((condition, body)) = condition_and_body(&rest[1..]) else {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("expected `(<condition>) {{ ... }}` after `{sigil}else if`"),
+                    ));
+                };
+                let matched = crate::conditional::eval(condition, cx)?;
+                if chosen.is_none() && matched {
+                    chosen = Some(body);
+                }
+                consumed += 3;
+            }
+            Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => {
+                chosen.get_or_insert(body);
+                consumed += 1;
+                break;
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    else_token.span(),
+                    format!("expected `{{ ... }}` or `if` after `{sigil}else`"),
+                ));
+            }
+        }
+    }
+    let tokens = chosen
+        .map(|body| substitute_at(&body.stream(), cx, depth))
+        .transpose()?
+        .unwrap_or_default();
+    Ok((tokens, consumed))
+}
+
+/// Expand a `( ... ) <separator> *` repetition following the sigil once per entry of the group,
+/// with the placeholders of that entry.
+///
+/// The separator is an optional punctuation token. Returns the expanded tokens and the number of
+/// consumed token trees.
+///
+/// Without a trailing `*`, the group is not a repetition and `None` is returned, since other
+/// sigils are valid operators before a parenthesized expression or pattern (e.g. `n @ (1 | 2)`).
+/// With the [strict sigil](STRICT_SIGIL), a missing `*` is an error instead.
+fn repetition(
+    tts: &[TokenTree],
+    cx: &Context<'_>,
+    depth: usize,
+) -> syn::Result<Option<(TokenStream2, usize)>> {
+    let TokenTree::Group(group) = &tts[0] else {
+        unreachable!("repetition without a group");
+    };
+    let is_star = |tt: &TokenTree| matches!(tt, TokenTree::Punct(p) if p.as_char() == '*');
+    let sigil = cx.arm.config.sigil;
+    let (separator, consumed) = match &tts[1..] {
+        [star, ..] if is_star(star) => (None, 2),
+        [TokenTree::Punct(separator), star, ..] if is_star(star) => (Some(separator), 3),
+        _ if sigil == STRICT_SIGIL => {
+            return Err(syn::Error::new(
+                group.span_close(),
+                format!("expected `*` or a separator followed by `*` after `{sigil}(...)`"),
+            ));
+        }
+        _ => return Ok(None),
+    };
+    let mut out = TokenStream2::new();
+    let mut combination = cx.combination.to_vec();
+    for index in 0..cx.count {
+        if index > 0 {
+            out.extend(separator.cloned().map(TokenTree::Punct));
+        }
+        combination[cx.group_index] = index;
+        let sibling = Context {
+            combination: &combination,
+            ..*cx
+        }
+        .with_group(cx.group_index);
+        out.extend(substitute_at(&group.stream(), &sibling, depth)?);
+    }
+    Ok(Some((out, consumed)))
+}
+
+/// Match a parenthesized condition followed by a braced body.
+fn condition_and_body(tts: &[TokenTree]) -> Option<(TokenStream2, &proc_macro2::Group)> {
+    match tts {
+        [TokenTree::Group(condition), TokenTree::Group(body), ..]
+            if condition.delimiter() == Delimiter::Parenthesis
+                && body.delimiter() == Delimiter::Brace =>
+        {
+            Some((condition.stream(), body))
+        }
+        _ => None,
+    }
+}
+
+/// Match a sigil with `levels` scope prefixes, followed by `else`.
+fn else_access(tts: &[TokenTree], sigil: char, levels: usize) -> Option<&Ident> {
+    match tts.first() {
+        Some(TokenTree::Punct(p)) if p.as_char() == sigil => {}
+        _ => return None,
+    }
+    if scope_levels(&tts[1..]) != levels {
+        return None;
+    }
+    match tts.get(1 + levels) {
+        Some(TokenTree::Ident(ident)) if ident == "else" => Some(ident),
+        _ => None,
+    }
+}
+
+/// Substitute placeholders inside a delimited group, keeping its delimiter.
+fn substitute_group(
+    group: &proc_macro2::Group,
+    cx: &Context<'_>,
+    depth: usize,
+) -> syn::Result<TokenTree> {
+    let inner = substitute_at(&group.stream(), cx, depth)?;
+    Ok(proc_macro2::Group::new(group.delimiter(), inner).into())
+}
+
+/// Returns `true` if `ident` starts a nested `delegate_match! { ... }` invocation.
+fn is_nested_invocation(ident: &Ident, rest: &[TokenTree]) -> bool {
+    matches!(
+        rest,
+        [TokenTree::Punct(bang), TokenTree::Group(_), ..] if bang.as_char() == '!'
+    ) && ident == "delegate_match"
+}
+
+/// Count the `^` scope prefixes following a sigil.
+fn scope_levels(tts: &[TokenTree]) -> usize {
+    tts.iter()
+        .take_while(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == '^'))
+        .count()
+}
+
+/// Error for a placeholder whose scope prefix reaches past the outermost invocation.
+fn unscoped_error(sigil: char, levels: usize, ident: &Ident) -> syn::Error {
+    syn::Error::new(
+        ident.span(),
+        format!(
+            "`{sigil}{}{ident}` does not refer to an enclosing `delegate_match!` invocation",
+            "^".repeat(levels)
+        ),
+    )
+}
+
+/// Error for a token that cannot be part of an identifier built with `$ident(...)`.
+fn fragment_error(tt: &TokenTree) -> syn::Error {
+    syn::Error::new(
+        tt.span(),
+        format!("`{tt}` cannot be part of an identifier, expected an identifier or a literal"),
+    )
+}
+
+/// Error for an identifier following the sigil that is not a known placeholder.
+fn unknown_error(sigil: char, levels: usize, ident: &Ident) -> syn::Error {
+    let name = ident.to_string();
+    let scope = "^".repeat(levels);
+    let hint = if name == ASSOC {
+        format!(", expected `{sigil}{scope}{ASSOC}.<name>`")
+    } else {
+        crate::util::closest_match(&name, PLACEHOLDERS)
+            .map(|suggestion| format!(", did you mean `{sigil}{scope}{suggestion}`?"))
+            .unwrap_or_default()
+    };
+    let valid = PLACEHOLDERS
+        .iter()
+        .map(|p| format!("`{sigil}{p}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let message = format!(
+        "unknown placeholder `{sigil}{scope}{name}`{hint}\n\
+         valid placeholders are: {valid}\n\
+         write `{sigil}{sigil}{name}` for a literal `{sigil}{name}`"
+    );
+    syn::Error::new(ident.span(), message)
+}
+
+/// Substitute placeholders inside a string literal with their stringified tokens.
+///
+/// The literal keeps its original span.
+fn interpolate(literal: &Literal, cx: &Context<'_>, depth: usize) -> syn::Result<TokenTree> {
+    let syn::Lit::Str(lit) = syn::Lit::new(literal.clone()) else {
+        return Ok(literal.clone().into());
+    };
+    let sigil = cx.arm.config.sigil;
+    let value = lit.value();
+    if !lit.suffix().is_empty() || !value.contains(sigil) {
+        return Ok(literal.clone().into());
+    }
+    debug_trace!("interpolating string literal: {}", literal);
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value.as_str();
+    while let Some(pos) = rest.find(sigil) {
+        result.push_str(&rest[..pos]);
+        let after_sigil = &rest[pos + sigil.len_utf8()..];
+        let placeholder = after_sigil.trim_start_matches('^');
+        let levels = after_sigil.len() - placeholder.len();
+        // A doubled sigil is an escape for a single literal sigil.
+        if depth == 0 && levels == 0 && placeholder.starts_with(sigil) {
+            result.push(sigil);
+            rest = &placeholder[sigil.len_utf8()..];
+            continue;
+        }
+        let candidate = placeholder_text(placeholder);
+        let mut tts: Vec<TokenTree> = candidate
+            .parse::<TokenStream2>()
+            .map(|ts| ts.into_iter().collect())
+            .unwrap_or_default();
+        for tt in &mut tts {
+            tt.set_span(literal.span());
+        }
+        if levels > depth {
+            if let Some(TokenTree::Ident(ident)) = tts.first() {
+                return Err(unscoped_error(sigil, levels, ident));
+            }
+        }
+        let resolved = if levels == depth {
+            cx.resolve(&tts, depth)?
+        } else {
+            None
+        };
+        if let Some((replacement, consumed)) = resolved {
+            // Modifiers that produce a string literal insert its value instead.
+            let has_modifier = tts[..consumed]
+                .iter()
+                .any(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == ':'));
+            match syn::parse2::<syn::LitStr>(replacement.clone()) {
+                Ok(lit) if has_modifier => result.push_str(&lit.value()),
+                _ => result.push_str(&replacement.to_string()),
+            }
+            // The candidate text has no whitespace, so the consumed tokens map back to it exactly.
+            let len: usize = tts[..consumed].iter().map(|tt| tt.to_string().len()).sum();
+            rest = &placeholder[len..];
+        } else {
+            result.push(sigil);
+            rest = after_sigil;
+        }
+    }
+    result.push_str(rest);
+    Ok(syn::LitStr::new(&result, lit.span()).token().into())
+}
+
+/// Extract the text of a potential placeholder at the start of `s`, which follows the sigil.
+///
+/// The text includes an optional `N.` group prefix, an optional `.accessor` and an optional
+/// `:modifier`.
+fn placeholder_text(s: &str) -> &str {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if digits > 0 && s[digits..].starts_with('.') {
+        let rest = placeholder_text(&s[digits + 1..]);
+        if !rest.is_empty() {
+            return &s[..digits + 1 + rest.len()];
+        }
+    }
+    let is_ident_char = |c: char| c == '_' || c.is_ascii_alphanumeric();
+    let ident_len = |s: &str| s.find(|c| !is_ident_char(c)).unwrap_or(s.len());
+    let mut len = ident_len(s);
+    if len == 0 {
+        return "";
+    }
+    for separator in ['.', ':'] {
+        if s[len..].starts_with(separator) {
+            let suffix_len = ident_len(&s[len + 1..]);
+            if suffix_len > 0 {
+                len += 1 + suffix_len;
             }
-            other => out.push(other),
         }
     }
+    &s[..len]
+}
+
+/// Match a `N.` group prefix following the sigil, e.g. `2.` in `$2.entry_pat`.
+fn group_access(tts: &[TokenTree]) -> Option<&Literal> {
+    match tts {
+        [TokenTree::Literal(number), TokenTree::Punct(dot), TokenTree::Ident(_), ..]
+            if dot.as_char() == '.' && number.to_string().bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            Some(number)
+        }
+        _ => None,
+    }
+}
+
+/// Return the identifier naming the placeholder following the sigil, after any group prefix.
+fn placeholder_ident(tts: &[TokenTree]) -> Option<&TokenTree> {
+    let skip = if group_access(tts).is_some() { 2 } else { 0 };
+    tts.get(skip)
+}
+
+/// Match a `(or: <default>)` group following a placeholder, returning the default tokens.
+fn default_access(tts: &[TokenTree]) -> Option<TokenStream2> {
+    let Some(TokenTree::Group(group)) = tts.first() else {
+        return None;
+    };
+    if group.delimiter() != Delimiter::Parenthesis {
+        return None;
+    }
+    let mut inner = group.stream().into_iter();
+    match (inner.next(), inner.next()) {
+        (Some(TokenTree::Ident(or)), Some(TokenTree::Punct(colon)))
+            if or == "or"
+                && colon.as_char() == ':'
+                && colon.spacing() == proc_macro2::Spacing::Alone =>
+        {
+            Some(inner.collect())
+        }
+        _ => None,
+    }
+}
+
+/// Match a `:modifier` following a placeholder.
+fn modifier_access(tts: &[TokenTree]) -> Option<&Ident> {
+    match tts {
+        [TokenTree::Punct(colon), TokenTree::Ident(modifier), ..]
+            if colon.as_char() == ':'
+                && colon.spacing() == proc_macro2::Spacing::Alone
+                && MODIFIERS.iter().any(|m| modifier == m) =>
+        {
+            Some(modifier)
+        }
+        _ => None,
+    }
+}
+
+/// Apply a `:modifier` to the replacement tokens of a placeholder.
+///
+/// `:str` produces a string literal of the token text. Case conversions require the tokens to be
+/// a single identifier, and produce an identifier, except for `:kebab` which produces a string.
+fn apply_modifier(tokens: &TokenStream2, modifier: &Ident) -> syn::Result<TokenStream2> {
+    let name = modifier.to_string();
+    if name == STR {
+        let lit = syn::LitStr::new(&tokens.to_string(), modifier.span());
+        return Ok(lit.into_token_stream());
+    }
+    let ident = single_ident(tokens.clone()).ok_or_else(|| {
+        syn::Error::new(
+            modifier.span(),
+            format!(
+                "`:{name}` requires a placeholder that expands to an identifier, found `{tokens}`"
+            ),
+        )
+    })?;
+    let text = ident.unraw().to_string();
+    let converted = match name.as_str() {
+        SNAKE => case::join(&text, "_", case::lower),
+        KEBAB => case::join(&text, "-", case::lower),
+        SCREAMING => case::join(&text, "_", case::upper),
+        PASCAL => case::join(&text, "", case::capitalize),
+        CAMEL => {
+            let pascal = case::join(&text, "", case::capitalize);
+            let mut chars = pascal.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_lowercase().chain(chars).collect()
+            })
+        }
+        _ => unreachable!("unknown modifier: {name}"),
+    };
+    if name == KEBAB {
+        return Ok(syn::LitStr::new(&converted, ident.span()).into_token_stream());
+    }
+    // Keywords are turned into raw identifiers, e.g. `Type` becomes `r#type`.
+    syn::parse_str::<Ident>(&converted)
+        .or_else(|_| syn::parse_str::<Ident>(&format!("r#{converted}")))
+        .map(|mut converted| {
+            converted.set_span(ident.span());
+            converted.into_token_stream()
+        })
+        .map_err(|_| {
+            syn::Error::new(
+                modifier.span(),
+                format!("`{converted}` is not a valid identifier"),
+            )
+        })
+}
+
+/// Return the identifier if `tokens` consist of exactly one, possibly in invisible groups.
+fn single_ident(tokens: TokenStream2) -> Option<Ident> {
+    let mut iter = tokens.into_iter();
+    let tt = iter.next()?;
+    if iter.next().is_some() {
+        return None;
+    }
+    match tt {
+        TokenTree::Ident(ident) => Some(ident),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            single_ident(group.stream())
+        }
+        _ => None,
+    }
+}
+
+/// Match a `.name` accessor following a placeholder.
+fn named_access(tts: &[TokenTree]) -> Option<&proc_macro2::Ident> {
+    match tts {
+        [TokenTree::Punct(dot), TokenTree::Ident(name), ..] if dot.as_char() == '.' => Some(name),
+        _ => None,
+    }
+}
+
+/// Match a `.N` accessor following a placeholder.
+fn index_access(tts: &[TokenTree]) -> Option<usize> {
+    match tts {
+        [TokenTree::Punct(dot), TokenTree::Literal(index), ..] if dot.as_char() == '.' => {
+            let index = index.to_string();
+            index
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| index.parse().ok())
+                .flatten()
+        }
+        _ => None,
+    }
+}
+
+/// Create an unsuffixed integer literal spanned at the placeholder.
+fn usize_literal(value: usize, placeholder: &Ident) -> TokenStream2 {
+    let mut literal = Literal::usize_unsuffixed(value);
+    literal.set_span(placeholder.span());
+    TokenTree::from(literal).into()
+}
 
-    let result: TokenStream2 = out.into_iter().collect();
-    debug_trace!("substitution result: {}", result);
-    result
+/// Create a `true` or `false` literal spanned at the placeholder.
+fn bool_literal(value: bool, placeholder: &Ident) -> TokenStream2 {
+    let value = if value { "true" } else { "false" };
+    TokenTree::from(Ident::new(value, placeholder.span())).into()
 }
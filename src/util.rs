@@ -67,3 +67,92 @@ macro_rules! debug_trace {
 }
 
 pub(crate) use debug_trace;
+
+/// Levenshtein distance between two strings, counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Find the candidate closest to `name`, if it is close enough to be a likely typo.
+pub fn closest_match<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|&candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Case conversion of identifiers, e.g. for the `$entry_ident:snake` placeholder modifier.
+pub mod case {
+    /// Split an identifier into words at underscores, hyphens and case boundaries.
+    ///
+    /// For example, `HTTPServer_v2` is split into `HTTP`, `Server` and `v2`.
+    pub fn words(s: &str) -> Vec<String> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut words = Vec::new();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            if c == '_' || c == '-' {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            if c.is_uppercase() && !current.is_empty() {
+                let prev = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+                if prev.is_lowercase() || prev.is_numeric() || next_is_lower {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+        words
+    }
+
+    /// Join the words of an identifier with `separator`, transforming each word with `f`.
+    pub fn join(s: &str, separator: &str, f: fn(&str) -> String) -> String {
+        words(s)
+            .iter()
+            .map(|word| f(word))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    pub fn lower(word: &str) -> String {
+        word.to_lowercase()
+    }
+
+    pub fn upper(word: &str) -> String {
+        word.to_uppercase()
+    }
+
+    /// Uppercase the first character and lowercase the rest.
+    pub fn capitalize(word: &str) -> String {
+        let mut chars = word.chars();
+        chars.next().map_or_else(String::new, |first| {
+            first
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect()
+        })
+    }
+}
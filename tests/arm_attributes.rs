@@ -0,0 +1,39 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Codec {
+    Gzip,
+    Zstd,
+    Brotli,
+}
+
+fn enabled(codec: Codec) -> bool {
+    delegate_match! {
+        match codec {
+            // Each generated arm is gated on its own predicate.
+            #[cfg($assoc_ts)]
+            Codec::{ Gzip: all(), Zstd: any(), Brotli: not(any()) } => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+fn lint_name(codec: Codec) -> &'static str {
+    delegate_match! {
+        match codec {
+            #[allow(clippy::$assoc_ts)]
+            Codec::{ Gzip: match_same_arms, Zstd: match_same_arms, Brotli: match_same_arms } => {
+                stringify!($entry_pat)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_arm_attributes() {
+    assert!(enabled(Codec::Gzip));
+    assert!(!enabled(Codec::Zstd));
+    assert!(enabled(Codec::Brotli));
+    assert_eq!(lint_name(Codec::Zstd), "Zstd");
+}
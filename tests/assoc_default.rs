@@ -0,0 +1,33 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Level {
+    Low,
+    Mid,
+    High,
+}
+
+fn weight(level: Level) -> u32 {
+    delegate_match! {
+        match level {
+            Level::{ Low, Mid: 5, High: 10 } => $assoc_ts(or: 1),
+        }
+    }
+}
+
+fn call_with(level: Level, f: fn(u32) -> u32) -> u32 {
+    delegate_match! {
+        match level {
+            // A parenthesized group without `or:` is a normal call.
+            Level::{ Low: f, Mid: f, High: f } => $assoc_ts(2),
+        }
+    }
+}
+
+#[test]
+fn test_assoc_default() {
+    assert_eq!(weight(Level::Low), 1);
+    assert_eq!(weight(Level::Mid), 5);
+    assert_eq!(weight(Level::High), 10);
+    assert_eq!(call_with(Level::Mid, |x| x * 3), 6);
+}
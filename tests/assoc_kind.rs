@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Store {
+    Map,
+    Vec,
+}
+
+fn len(store: Store) -> usize {
+    delegate_match! {
+        match store {
+            // Without the prefix, `HashMap<u8, u8>` would be parsed as the expression `HashMap < u8`.
+            Store::{ Map: type HashMap<u8, u8>, Vec: type Vec<(u8, u8)> } => {
+                let mut items = <$assoc_ts>::default();
+                items.extend([(1, 2), (3, 4)]);
+                items.len()
+            }
+        }
+    }
+}
+
+fn first(value: Option<u8>, store: Store) -> u8 {
+    delegate_match! {
+        match store {
+            Store::{ Map: pat Some(1 | 2), Vec: pat Some(_) } => match value {
+                $assoc_ts => 1,
+                _ => 0,
+            },
+        }
+    }
+}
+
+fn helper(store: Store) -> &'static str {
+    delegate_match! {
+        match store {
+            #![delegate(uniform_assoc)]
+            Store::{
+                Map: item fn helper() -> &'static str { "map" },
+                Vec: item fn helper() -> &'static str { "vec" },
+            } => {
+                $assoc_ts
+                helper()
+            }
+        }
+    }
+}
+
+fn scaled(store: Store) -> u8 {
+    let expr = 2;
+    delegate_match! {
+        match store {
+            // A lone `expr` or one that forms an expression with the following tokens is not a prefix.
+            Store::{ Map: expr 3, Vec: expr * 2 } => $assoc_ts,
+        }
+    }
+}
+
+fn item_len(store: Store) -> usize {
+    let item = [1, 2, 3];
+    delegate_match! {
+        match store {
+            Store::{ Map: item.len(), Vec: 0 } => $assoc_ts,
+        }
+    }
+}
+
+fn name(store: Store) -> &'static str {
+    delegate_match! {
+        match store {
+            Store::{ Map: type &'static str, Vec: type &'static str } => {
+                let name: $assoc_ts = stringify!($entry_ident);
+                name
+            }
+        }
+    }
+}
+
+fn is_present(value: &Option<u8>, store: Store) -> bool {
+    delegate_match! {
+        match store {
+            Store::{ Map: pat &Some(ref x), Vec: pat &Some(ref x) } => matches!(value, $assoc_ts if *x > 0),
+        }
+    }
+}
+
+#[test]
+fn test_assoc_kind() {
+    assert_eq!(len(Store::Map), 2);
+    assert_eq!(len(Store::Vec), 2);
+    assert_eq!(first(Some(2), Store::Map), 1);
+    assert_eq!(first(Some(3), Store::Map), 0);
+    assert_eq!(first(Some(3), Store::Vec), 1);
+    assert_eq!(helper(Store::Map), "map");
+    assert_eq!(helper(Store::Vec), "vec");
+    assert_eq!(scaled(Store::Map), 3);
+    assert_eq!(scaled(Store::Vec), 4);
+    assert_eq!(item_len(Store::Map), 3);
+    assert_eq!(item_len(Store::Vec), 0);
+    assert_eq!(name(Store::Map), "Map");
+    assert_eq!(name(Store::Vec), "Vec");
+    assert!(is_present(&Some(1), Store::Vec));
+    assert!(!is_present(&Some(0), Store::Map));
+    assert!(!is_present(&None, Store::Map));
+}
@@ -0,0 +1,57 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+}
+
+fn handler(op: Op) -> String {
+    delegate_match! {
+        match op {
+            // Placeholders inside associated tokens refer to their own entry.
+            Op::{
+                Add: format!("handler_{}", stringify!($entry_pat)),
+                Sub: format!("handler_{}", stringify!($entry_pat)),
+                Mul: format!("{}_{}", stringify!($entry_ident), $entry_index),
+            } => $assoc_ts,
+        }
+    }
+}
+
+#[allow(clippy::identity_op, reason = "placeholders expand to literals")]
+fn weight(op: Op) -> usize {
+    delegate_match! {
+        match op {
+            // Not valid syntax until substituted.
+            Op::{ Add: $entry_index + 10, Sub: { 2 * $entry_count }, Mul: $entry_index * 3 } => {
+                $assoc_ts
+            }
+        }
+    }
+}
+
+#[allow(clippy::identity_op, reason = "placeholders expand to literals")]
+fn named(op: Op) -> (u8, u8) {
+    delegate_match! {
+        match op {
+            Op::{ Add: { base = 1, double = $assoc.base * 2 }, Sub: { base = 3, double = $assoc.base * 2 }, Mul: { base = 0, double = 0 } } => {
+                ($assoc.base, $assoc.double)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_assoc_placeholders() {
+    assert_eq!(handler(Op::Add), "handler_Add");
+    assert_eq!(handler(Op::Sub), "handler_Sub");
+    assert_eq!(handler(Op::Mul), "Mul_2");
+    assert_eq!(weight(Op::Add), 10);
+    assert_eq!(weight(Op::Sub), 6);
+    assert_eq!(weight(Op::Mul), 6);
+    assert_eq!(named(Op::Add), (1, 2));
+    assert_eq!(named(Op::Sub), (3, 6));
+    assert_eq!(named(Op::Mul), (0, 0));
+}
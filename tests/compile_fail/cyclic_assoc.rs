@@ -0,0 +1,15 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let num = Num::A(1);
+    let _ = delegate_match! {
+        match num {
+            Num::{ A: { x = $assoc.y, y = $assoc.x }, B: { x = 1, y = 2 } }(_) => $assoc.x,
+        }
+    };
+}
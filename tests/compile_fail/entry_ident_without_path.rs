@@ -0,0 +1,11 @@
+use delegate_match::delegate_match;
+
+fn main() {
+    delegate_match! {
+        match 1 {
+            // Literal entry patterns do not have an identifier.
+            { 1, 2 } => println!("{}", stringify!($entry_ident)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use delegate_match::delegate_match;
+
+enum Inner {
+    A(u8),
+}
+
+enum Outer {
+    Inner(Inner),
+    B(u8),
+}
+
+fn main() {
+    let outer = Outer::B(1);
+    let _ = delegate_match! {
+        match outer {
+            Outer::{ Inner::{ A }, B }(x) => { let _ = stringify!($entry_pat.parent); x }
+        }
+    };
+}
@@ -0,0 +1,15 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let pair = (Num::A(1), Num::B(2));
+    let _ = delegate_match! {
+        match pair {
+            (Num::{ A, B }(x), Num::{ A, B }(y)) => x + y + $3.entry_index,
+        }
+    };
+}
@@ -0,0 +1,17 @@
+use delegate_match::delegate_match;
+
+enum Event {
+    Click,
+    Key,
+}
+
+fn handle_click() {}
+
+fn main() {
+    let event = Event::Click;
+    delegate_match! {
+        match event {
+            Event::{ Click: click, Key: key } => $ident(handle_, $assoc_ts)(),
+        }
+    }
+}
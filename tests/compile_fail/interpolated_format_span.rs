@@ -0,0 +1,16 @@
+use delegate_match::delegate_match;
+
+enum E {
+    A,
+    B,
+}
+
+fn main() {
+    delegate_match! {
+        match E::A {
+            #![delegate(interpolate_strings)]
+            // The interpolated literal keeps its span, so the format error points here.
+            E::{ A, B } => println!("$entry_pat: {missing}"),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let num = Num::A(1);
+    delegate_match! {
+        match num {
+            Num::{ A, B }(x) => $if(variant = A) { x } $else { 0 },
+        }
+    };
+}
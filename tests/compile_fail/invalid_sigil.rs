@@ -0,0 +1,15 @@
+use delegate_match::delegate_match;
+
+enum E {
+    A,
+}
+
+fn main() {
+    delegate_match! {
+        match E::A {
+            // Sigils must be a single supported punctuation character.
+            #![delegate(sigil = "$$")]
+            E::{ A } => {}
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use delegate_match::delegate_match;
+
+#[derive(Clone, Copy)]
+enum Digit {
+    A,
+    B,
+    C,
+}
+
+fn main() {
+    let pair = (Digit::A, Digit::B, Digit::C);
+    delegate_match! {
+        match pair {
+            #![delegate(max_arms = 8)]
+            (Digit::{ A, B, C }, Digit::{ A, B, C }, Digit::{ A, B, C }) => {}
+        }
+    }
+}
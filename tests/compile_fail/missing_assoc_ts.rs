@@ -0,0 +1,17 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn consume(_: u8) {}
+
+fn main() {
+    let num = Num::A(1);
+    delegate_match! {
+        match num {
+            Num::{ A: 1, B }(_) => consume($assoc_ts),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use delegate_match::delegate_match;
+
+enum E {
+    A,
+    B,
+}
+
+fn main() {
+    delegate_match! {
+        match E::A {
+            // `B` does not provide an item named `code`.
+            E::{ A: { code = 1, name = "a" }, B: { name = "b" } } => {
+                println!("{} {}", $assoc.name, $assoc.code)
+            }
+        }
+    }
+}
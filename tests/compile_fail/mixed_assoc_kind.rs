@@ -0,0 +1,16 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let num = Num::A(1);
+    delegate_match! {
+        match num {
+            #![delegate(uniform_assoc)]
+            Num::{ A: 1, B: type u8 }(_) => {}
+        }
+    }
+}
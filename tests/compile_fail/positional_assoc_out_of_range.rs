@@ -0,0 +1,17 @@
+use delegate_match::delegate_match;
+
+enum E {
+    A,
+    B,
+}
+
+fn main() {
+    delegate_match! {
+        match E::A {
+            // `B` only has two positional items.
+            E::{ A: (1, 2, 3), B: (4, 5) } => {
+                println!("{}", $assoc_ts.2)
+            }
+        }
+    }
+}
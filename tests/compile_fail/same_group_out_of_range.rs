@@ -0,0 +1,17 @@
+use delegate_match::delegate_match;
+
+#[derive(Clone, Copy)]
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let pair = (Num::A(1), Num::B(2));
+    let _ = delegate_match! {
+        match pair {
+            #[delegate(mismatch = 0)]
+            (Num::{ A, B }(x), Num::$2.same(y)) => x + y,
+        }
+    };
+}
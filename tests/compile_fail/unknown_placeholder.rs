@@ -0,0 +1,15 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let num = Num::A(1);
+    let _ = delegate_match! {
+        match num {
+            Num::{ A: 1, B: 2 }(n) => n + $asoc_ts,
+        }
+    };
+}
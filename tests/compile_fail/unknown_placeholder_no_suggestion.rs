@@ -0,0 +1,22 @@
+use delegate_match::delegate_match;
+
+enum Num {
+    A(u8),
+    B(u8),
+}
+
+fn main() {
+    let num = Num::A(1);
+    delegate_match! {
+        match num {
+            Num::{ A, B }(n) => {
+                macro_rules! double {
+                    ($x:expr) => {
+                        $x * 2
+                    };
+                }
+                double!(n)
+            }
+        }
+    };
+}
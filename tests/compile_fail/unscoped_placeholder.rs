@@ -0,0 +1,15 @@
+use delegate_match::delegate_match;
+
+enum Format {
+    Json,
+    Yaml,
+}
+
+fn main() {
+    let format = Format::Json;
+    delegate_match! {
+        match format {
+            Format::{ Json, Yaml } => println!("{}", stringify!($^entry_pat)),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use delegate_match::delegate_match;
+
+#[derive(Clone, Copy)]
+enum Src {
+    A,
+    B,
+}
+
+#[derive(Clone, Copy)]
+enum Dst {
+    X,
+    Y,
+    Z,
+}
+
+fn main() {
+    let pair = (Src::A, Dst::X);
+    delegate_match! {
+        match pair {
+            #[delegate(zip)]
+            (Src::{ A, B }, Dst::{ X, Y, Z }) => {}
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Codec {
+    Gzip(u32),
+    Zstd(i32),
+    Raw(()),
+}
+
+fn level(codec: Codec) -> i64 {
+    delegate_match! {
+        match codec {
+            Codec::{ Gzip, Zstd, Raw }(level) => {
+                // The branch not taken is removed, so `()` never has to support `clamp`.
+                let level = $if(entry = Gzip) {
+                    i64::from(level.clamp(1, 9))
+                } $else if(entry = Zstd) {
+                    i64::from(level)
+                } $else {
+                    // The outer braces delimit the branch, the inner ones form a block.
+                    {
+                        let () = level;
+                        0
+                    }
+                };
+                level
+            }
+        }
+    }
+}
+
+fn position(codec: Codec) -> &'static str {
+    delegate_match! {
+        match codec {
+            Codec::{ Gzip, Zstd, Raw }(_) => $if(is_first) { "first" } $else if(is_last) { "last" } $else { "middle" },
+        }
+    }
+}
+
+fn accepts(codec: Codec) -> bool {
+    delegate_match! {
+        match codec {
+            // Conditionals in the arm pattern and guard.
+            Codec::{ Gzip, Zstd }($if(index = 0) { 0..=9 } $else { _ }) if $if(entry != Gzip | Raw) { true } $else { false } => true,
+            Codec::{ Gzip, Zstd, Raw }(_) => false,
+        }
+    }
+}
+
+fn without_else(codec: Codec) -> u8 {
+    let mut flags = 0;
+    delegate_match! {
+        match codec {
+            Codec::{ Gzip, Zstd, Raw }(_) => {
+                $if(!is_last) { flags |= 1; }
+                $if(index != 1) { flags |= 2; }
+            }
+        }
+    }
+    flags
+}
+
+#[test]
+fn test_conditional() {
+    assert_eq!(level(Codec::Gzip(20)), 9);
+    assert_eq!(level(Codec::Zstd(-3)), -3);
+    assert_eq!(level(Codec::Raw(())), 0);
+    assert_eq!(position(Codec::Gzip(0)), "first");
+    assert_eq!(position(Codec::Zstd(0)), "middle");
+    assert_eq!(position(Codec::Raw(())), "last");
+    assert!(!accepts(Codec::Gzip(20)));
+    assert!(accepts(Codec::Zstd(20)));
+    assert!(!accepts(Codec::Raw(())));
+    assert_eq!(without_else(Codec::Gzip(0)), 3);
+    assert_eq!(without_else(Codec::Zstd(0)), 1);
+    assert_eq!(without_else(Codec::Raw(())), 2);
+}
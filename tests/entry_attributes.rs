@@ -0,0 +1,47 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Backend {
+    #[cfg(any())]
+    Postgres,
+    Sqlite,
+    Memory,
+    #[deprecated]
+    Legacy,
+}
+
+#[allow(deprecated)]
+fn name(backend: Backend) -> &'static str {
+    delegate_match! {
+        match backend {
+            // Entry attributes are carried onto the entry's own arm.
+            Backend::{
+                #[cfg(any())] Postgres: "postgres",
+                #[cfg(all())] Sqlite: "sqlite",
+                Memory: "memory",
+                #[allow(deprecated)] Legacy: "legacy",
+            } => $assoc_ts,
+        }
+    }
+}
+
+fn is_persistent(backend: Backend) -> bool {
+    delegate_match! {
+        match backend {
+            // Arm attributes come first, entry attributes may also contain placeholders.
+            #[allow(deprecated)]
+            Backend::{ #[cfg($assoc_ts)] Sqlite: all(), Memory: all(), Legacy: all() } => matches!(backend, Backend::Sqlite),
+        }
+    }
+}
+
+#[test]
+fn test_entry_attributes() {
+    assert_eq!(name(Backend::Sqlite), "sqlite");
+    assert_eq!(name(Backend::Memory), "memory");
+    #[allow(deprecated)]
+    let legacy = Backend::Legacy;
+    assert_eq!(name(legacy), "legacy");
+    assert!(is_persistent(Backend::Sqlite));
+    assert!(!is_persistent(Backend::Memory));
+}
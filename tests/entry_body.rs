@@ -0,0 +1,51 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    Circle(f64),
+    Square(f64),
+    Point(f64),
+}
+
+fn common(x: f64) -> f64 {
+    x * 2.0
+}
+
+fn special(x: f64) -> f64 {
+    -x
+}
+
+fn apply(shape: Shape) -> f64 {
+    delegate_match! {
+        match shape {
+            // `Point` reuses the arm path, arm pattern and guard, but has its own body.
+            Shape::{ Circle, Square, Point => special(x) }(x) if x >= 0.0 => common(x),
+            Shape::{ Circle, Square, Point }(_) => 0.0,
+        }
+    }
+}
+
+fn describe(shape: Shape) -> String {
+    delegate_match! {
+        match shape {
+            // Overrides may be block-like, and may use placeholders too.
+            Shape::{
+                Circle: "round",
+                Square: "square" => { format!("{} {}", $assoc_ts, x * 2.0) }
+                Point: "dot" if x == 0.0 => format!("{} origin", $assoc_ts),
+            }(x) => format!("{} {x}", $assoc_ts),
+            Shape::Point(x) => format!("dot {x}"),
+        }
+    }
+}
+
+#[test]
+fn test_entry_body() {
+    assert!((apply(Shape::Circle(1.0)) - 2.0).abs() < f64::EPSILON);
+    assert!((apply(Shape::Point(1.0)) + 1.0).abs() < f64::EPSILON);
+    assert!(apply(Shape::Point(-1.0)).abs() < f64::EPSILON);
+    assert_eq!(describe(Shape::Circle(1.0)), "round 1");
+    assert_eq!(describe(Shape::Square(1.0)), "square 2");
+    assert_eq!(describe(Shape::Point(0.0)), "dot origin");
+    assert_eq!(describe(Shape::Point(2.0)), "dot 2");
+}
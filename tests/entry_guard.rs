@@ -0,0 +1,40 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    I32(i32),
+    I64(i64),
+}
+
+fn classify(num: Num, strict: bool) -> &'static str {
+    delegate_match! {
+        match num {
+            // The entry guard only applies to `I32`, and is combined with the arm guard.
+            Num::{ I32 if v > 0, I64 }(v) if strict || v != 0 => "accepted",
+            Num::{ I32, I64 }(_) => "rejected",
+        }
+    }
+}
+
+fn scaled(num: Num) -> i64 {
+    delegate_match! {
+        match num {
+            // Guards may follow associated tokens, and may contain placeholders.
+            Num::{ I32: 2 if v < $assoc_ts, I64: 3 if v < $assoc_ts }(v) => { i64::from(v) * $assoc_ts }
+            Num::{ I32, I64 }(v) => i64::from(v),
+        }
+    }
+}
+
+#[test]
+fn test_entry_guard() {
+    assert_eq!(classify(Num::I32(1), false), "accepted");
+    assert_eq!(classify(Num::I32(-1), true), "rejected");
+    assert_eq!(classify(Num::I64(-1), false), "accepted");
+    assert_eq!(classify(Num::I64(0), false), "rejected");
+    assert_eq!(classify(Num::I64(0), true), "accepted");
+    assert_eq!(scaled(Num::I32(1)), 2);
+    assert_eq!(scaled(Num::I32(5)), 5);
+    assert_eq!(scaled(Num::I64(2)), 6);
+    assert_eq!(scaled(Num::I64(3)), 3);
+}
@@ -0,0 +1,38 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Codec {
+    Gzip,
+    Zstd,
+    Lz4,
+}
+
+impl Codec {
+    fn tag(self) -> (u8, usize, bool, bool) {
+        delegate_match! {
+            match self {
+                // Entry metadata is substituted with literals.
+                Codec::{ Gzip, Zstd, Lz4 } => ($entry_index, $entry_count, $is_first, $is_last),
+            }
+        }
+    }
+
+    fn has_tag(self, tag: u8) -> bool {
+        delegate_match! {
+            match self {
+                // Metadata placeholders are also available inside the guard.
+                Codec::{ Gzip, Zstd, Lz4 } if tag == $entry_index => true,
+                _ => false,
+            }
+        }
+    }
+}
+
+#[test]
+fn test_entry_metadata() {
+    assert_eq!(Codec::Gzip.tag(), (0, 3, true, false));
+    assert_eq!(Codec::Zstd.tag(), (1, 3, false, false));
+    assert_eq!(Codec::Lz4.tag(), (2, 3, false, true));
+    assert!(Codec::Zstd.has_tag(1));
+    assert!(!Codec::Zstd.has_tag(2));
+}
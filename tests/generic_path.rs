@@ -0,0 +1,49 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+fn some_value(value: Option<u8>) -> u8 {
+    delegate_match! {
+        match value {
+            Option::<u8>::{ Some }(x) => x + 1,
+            None => 0,
+        }
+    }
+}
+
+fn widen(value: Either<u8, u16>) -> u32 {
+    delegate_match! {
+        match value {
+            // The comma inside the generic arguments does not end the pattern element.
+            Either::<u8, u16>::{ Left, Right }(x) => u32::from(x),
+        }
+    }
+}
+
+fn both(a: Either<u8, u16>, b: Either<u8, u16>) -> Option<u32> {
+    delegate_match! {
+        match (a, b) {
+            (Either::<u8, u16>::{ Left, Right }(x), Either::<u8, u16>::$same(y)) => {
+                Some(u32::from(x) + u32::from(y))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_generic_path() {
+    assert_eq!(some_value(Some(7)), 8);
+    assert_eq!(some_value(None), 0);
+
+    assert_eq!(widen(Either::Left(1)), 1);
+    assert_eq!(widen(Either::Right(300)), 300);
+
+    assert_eq!(both(Either::Left(1), Either::Left(2)), Some(3));
+    assert_eq!(both(Either::Right(3), Either::Right(4)), Some(7));
+    assert_eq!(both(Either::Left(1), Either::Right(2)), None);
+}
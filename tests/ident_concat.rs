@@ -0,0 +1,52 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Event {
+    Click,
+    Key,
+}
+
+#[allow(non_snake_case, reason = "intentional test case")]
+fn handle_Click() -> &'static str {
+    "click"
+}
+
+#[allow(non_snake_case, reason = "intentional test case")]
+fn handle_Key() -> &'static str {
+    "key"
+}
+
+#[allow(non_snake_case, reason = "intentional test case")]
+fn on_Key_2() -> u8 {
+    2
+}
+
+#[allow(non_snake_case, reason = "intentional test case")]
+fn on_Click_2() -> u8 {
+    1
+}
+
+fn handle(event: Event) -> &'static str {
+    delegate_match! {
+        match event {
+            Event::{ Click, Key } => $ident(handle_, $entry_ident)(),
+        }
+    }
+}
+
+fn on(event: Event) -> u8 {
+    delegate_match! {
+        match event {
+            // Literal fragments and nested placeholders.
+            Event::{ Click: 2, Key: 2 } => $ident("on_", $entry_ident, _, $assoc_ts)(),
+        }
+    }
+}
+
+#[test]
+fn test_ident_concat() {
+    assert_eq!(handle(Event::Click), "click");
+    assert_eq!(handle(Event::Key), "key");
+    assert_eq!(on(Event::Click), 1);
+    assert_eq!(on(Event::Key), 2);
+}
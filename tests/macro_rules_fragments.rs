@@ -0,0 +1,132 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug)]
+enum Data {
+    Small(u8),
+    Big(u64),
+    Other(u8),
+}
+
+impl Data {
+    fn small(v: u8) -> Self {
+        Self::Small(v)
+    }
+}
+
+// Forwarded `path` fragment as the arm path.
+macro_rules! with_path {
+    ($value:expr, $path:path) => {
+        delegate_match! {
+            match $value {
+                $path::{ Small, Big, Other }(v) => u64::from(*v),
+            }
+        }
+    };
+}
+
+// Forwarded `pat` fragments as entries.
+macro_rules! with_entries {
+    ($value:expr, $($entry:pat),*) => {
+        delegate_match! {
+            match $value {
+                Data::{ $($entry),* }(v) => u64::from(*v),
+                #[allow(unreachable_patterns, reason = "entries may cover all variants")]
+                _ => 0,
+            }
+        }
+    };
+}
+
+// Forwarded `pat` fragments used through structural placeholders.
+macro_rules! entry_name {
+    ($value:expr, $($entry:pat),*) => {
+        delegate_match! {
+            match $value {
+                #![delegate(sigil = "@")]
+                Data::{ $($entry),* }(_) => stringify!(@entry_ident),
+            }
+        }
+    };
+}
+
+// Forwarded `ty` and `expr` fragments as associated items.
+macro_rules! with_assoc {
+    ($value:expr, $small_ty:ty, $big_expr:expr, $tuple:expr) => {
+        delegate_match! {
+            match $value {
+                #![delegate(sigil = "@")]
+                Data::{ Small: $small_ty, Other: $small_ty }(v) => <@assoc_ts>::from(*v) as u64,
+                Data::{ Big: ($big_expr, $tuple) }(v) => *v + @assoc_ts.0 + @assoc_ts.1,
+            }
+        }
+    };
+}
+
+// Forwarded `pat` fragment in the arm pattern, `expr` fragments as guard and body.
+macro_rules! with_arm {
+    ($value:expr, $pat:pat, $guard:expr, $body:expr, $fallback:expr) => {
+        delegate_match! {
+            match $value {
+                Data::{ Small, Other }($pat) if $guard => $body,
+                Data::{ Small, Other }(_) => $fallback,
+                Data::Big(_) => 0,
+            }
+        }
+    };
+}
+
+// Forwarded block-like `expr` fragment as a body without a trailing comma,
+// followed by a forwarded `pat` fragment that starts with parentheses.
+macro_rules! with_block_body {
+    ($value:expr, $body:expr, $rest:pat) => {
+        delegate_match! {
+            match $value {
+                #![delegate(sigil = "@")]
+                { (Data::Small(_), true): "small", (Data::Other(_), true): "other" } => $body
+                $rest => "rest",
+            }
+        }
+    };
+}
+
+#[test]
+fn test_forwarded_path() {
+    assert_eq!(with_path!(&Data::Small(1), Data), 1);
+    assert_eq!(with_path!(&Data::Big(2), Data), 2);
+}
+
+#[test]
+fn test_forwarded_entries() {
+    assert_eq!(with_entries!(&Data::Small(1), Small, Big), 1);
+    assert_eq!(with_entries!(&Data::Big(2), Small | Other, Big), 2);
+    assert_eq!(with_entries!(&Data::Other(3), Small | Other, Big), 3);
+    assert_eq!(with_entries!(&Data::Other(3), Small, Big), 0);
+}
+
+#[test]
+fn test_forwarded_entry_name() {
+    assert_eq!(entry_name!(Data::Big(1), Small, Big, Other), "Big");
+    assert_eq!(entry_name!(Data::Other(1), Small, Big, Other), "Other");
+}
+
+#[test]
+fn test_forwarded_assoc() {
+    assert_eq!(with_assoc!(&Data::Small(1), u16, 10, 100), 1);
+    assert_eq!(with_assoc!(&Data::Big(1), u16, 10, 100), 111);
+}
+
+#[test]
+fn test_forwarded_arm() {
+    assert_eq!(with_arm!(Data::small(1), v, v > 0, u64::from(v) * 2, 7), 2);
+    assert_eq!(with_arm!(Data::Small(0), v, v > 0, u64::from(v) * 2, 7), 7);
+    assert_eq!(with_arm!(Data::Other(3), v, v > 0, u64::from(v) * 2, 7), 6);
+}
+
+#[test]
+fn test_forwarded_block_body() {
+    let body =
+        |data: Data, flag: bool| with_block_body!((data, flag), { concat!(@assoc_ts) }, (_, _));
+    assert_eq!(body(Data::Small(1), true), "small");
+    assert_eq!(body(Data::Other(1), true), "other");
+    assert_eq!(body(Data::Small(1), false), "rest");
+}
@@ -0,0 +1,80 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Codec {
+    Gzip,
+    ZstdFast,
+    HTTPChunked,
+}
+
+mod levels {
+    pub const GZIP: u8 = 1;
+    pub const ZSTD_FAST: u8 = 2;
+    pub const HTTP_CHUNKED: u8 = 3;
+}
+
+fn gzip() -> &'static str {
+    "gzip()"
+}
+
+fn zstd_fast() -> &'static str {
+    "zstd_fast()"
+}
+
+fn http_chunked() -> &'static str {
+    "http_chunked()"
+}
+
+fn flag(codec: Codec) -> &'static str {
+    delegate_match! {
+        match codec {
+            Codec::{ Gzip, ZstdFast, HTTPChunked } => $entry_ident:kebab,
+        }
+    }
+}
+
+fn level(codec: Codec) -> u8 {
+    delegate_match! {
+        match codec {
+            Codec::{ Gzip, ZstdFast, HTTPChunked } => levels::$entry_ident:SCREAMING,
+        }
+    }
+}
+
+fn call(codec: Codec) -> &'static str {
+    delegate_match! {
+        match codec {
+            Codec::{ Gzip, ZstdFast, HTTPChunked } => $entry_ident:snake(),
+        }
+    }
+}
+
+fn names(codec: Codec) -> [&'static str; 3] {
+    delegate_match! {
+        match codec {
+            #![delegate(interpolate_strings)]
+            Codec::{ Gzip: ZstdFast, ZstdFast: ZstdFast, HTTPChunked: ZstdFast } => [
+                $entry_pat:str,
+                stringify!($assoc_ts:camel),
+                "$entry_ident:pascal/$entry_ident:kebab",
+            ],
+        }
+    }
+}
+
+#[test]
+fn test_modifiers() {
+    assert_eq!(flag(Codec::Gzip), "gzip");
+    assert_eq!(flag(Codec::ZstdFast), "zstd-fast");
+    assert_eq!(flag(Codec::HTTPChunked), "http-chunked");
+    assert_eq!(level(Codec::Gzip), 1);
+    assert_eq!(level(Codec::ZstdFast), 2);
+    assert_eq!(level(Codec::HTTPChunked), 3);
+    assert_eq!(call(Codec::ZstdFast), "zstd_fast()");
+    assert_eq!(call(Codec::HTTPChunked), "http_chunked()");
+    assert_eq!(
+        names(Codec::HTTPChunked),
+        ["HTTPChunked", "zstdFast", "HttpChunked/http-chunked"]
+    );
+    assert_eq!(call(Codec::Gzip), gzip());
+}
@@ -0,0 +1,101 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Fmt {
+    Json(u8),
+    Cbor(u16),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Codec {
+    Gzip(u32),
+    Zstd(u64),
+}
+
+fn describe(fmt: Fmt, codec: Codec) -> String {
+    delegate_match! {
+        match (fmt, codec) {
+            #![delegate(interpolate_strings)]
+            (Fmt::{ Json: "json", Cbor: "cbor" }(f), Codec::{ Gzip: "gz", Zstd: "zst" }(c)) => {
+                format!("{}.{} $1.entry_ident/$2.entry_ident:snake {f} {c}", $1.assoc_ts, $2.assoc_ts)
+            }
+        }
+    }
+}
+
+#[allow(
+    clippy::identity_op,
+    clippy::erasing_op,
+    reason = "placeholders expand to literals"
+)]
+fn combination_index(fmt: Fmt, codec: Codec) -> usize {
+    delegate_match! {
+        match (fmt, codec) {
+            // Unprefixed placeholders refer to the first group.
+            (Fmt::{ Json, Cbor }(_), Codec::{ Gzip, Zstd }(_)) => $entry_index * $2.entry_count + $2.entry_index,
+        }
+    }
+}
+
+fn with_regular_element(fmt: Fmt, flag: bool) -> Option<u64> {
+    delegate_match! {
+        match (flag, fmt) {
+            // Regular patterns may be mixed with groups.
+            (true, Fmt::{ Json, Cbor }(f)) => Some(u64::from(f)),
+            (false, _) => None,
+        }
+    }
+}
+
+fn single_group(fmt: Fmt) -> u64 {
+    delegate_match! {
+        match fmt {
+            // The limit only applies to arms with multiple groups.
+            #![delegate(max_arms = 1)]
+            Fmt::{ Json, Cbor }(f) => u64::from(f),
+        }
+    }
+}
+
+fn all_pairs() -> Vec<&'static str> {
+    let mut pairs = Vec::new();
+    for fmt in [Fmt::Json(0), Fmt::Cbor(0)] {
+        for codec in [Codec::Gzip(0), Codec::Zstd(0)] {
+            delegate_match! {
+                match (fmt, codec) {
+                    (Fmt::{ Json, Cbor }(_), Codec::{ Gzip, Zstd }(_)) => {
+                        pairs.push(concat!(stringify!($1.entry_ident), "+", stringify!($2.entry_ident)));
+                    }
+                }
+            }
+        }
+    }
+    pairs
+}
+
+#[test]
+fn test_multiple_groups() {
+    assert_eq!(
+        describe(Fmt::Json(1), Codec::Gzip(2)),
+        "json.gz Json/gzip 1 2"
+    );
+    assert_eq!(
+        describe(Fmt::Cbor(3), Codec::Zstd(4)),
+        "cbor.zst Cbor/zstd 3 4"
+    );
+
+    assert_eq!(combination_index(Fmt::Json(0), Codec::Gzip(0)), 0);
+    assert_eq!(combination_index(Fmt::Json(0), Codec::Zstd(0)), 1);
+    assert_eq!(combination_index(Fmt::Cbor(0), Codec::Gzip(0)), 2);
+    assert_eq!(combination_index(Fmt::Cbor(0), Codec::Zstd(0)), 3);
+
+    assert_eq!(with_regular_element(Fmt::Cbor(7), true), Some(7));
+    assert_eq!(with_regular_element(Fmt::Json(7), false), None);
+
+    assert_eq!(single_group(Fmt::Cbor(5)), 5);
+
+    assert_eq!(
+        all_pairs(),
+        ["Json+Gzip", "Json+Zstd", "Cbor+Gzip", "Cbor+Zstd"]
+    );
+}
@@ -0,0 +1,41 @@
+use delegate_match::delegate_match;
+
+struct PingHandler;
+struct LogHandler;
+
+impl PingHandler {
+    const NAME: &'static str = "ping";
+}
+
+impl LogHandler {
+    const NAME: &'static str = "log";
+}
+
+#[derive(Debug)]
+enum Msg {
+    Ping(u8),
+    Log(u8),
+}
+
+fn describe(msg: &Msg) -> (&'static str, u8, &'static str) {
+    delegate_match! {
+        match msg {
+            // Each named item is available as its own `$assoc.name` placeholder.
+            Msg::{
+                Ping: { icon = "🏓", code = 1u8, ty = PingHandler },
+                Log: { icon = "📝", code = 2u8, ty = LogHandler },
+            }(offset) if *offset < $assoc.code => ($assoc.icon, *offset, <$assoc.ty>::NAME),
+            Msg::{ Ping: { code = 1u8 }, Log: { code = 2u8 } }(offset) => {
+                ("", *offset + $assoc.code, "")
+            }
+        }
+    }
+}
+
+#[test]
+fn test_named_assoc() {
+    assert_eq!(describe(&Msg::Ping(0)), ("🏓", 0, "ping"));
+    assert_eq!(describe(&Msg::Log(1)), ("📝", 1, "log"));
+    assert_eq!(describe(&Msg::Ping(1)), ("", 2, ""));
+    assert_eq!(describe(&Msg::Log(5)), ("", 7, ""));
+}
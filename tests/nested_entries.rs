@@ -0,0 +1,132 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Key(u32),
+    Mouse(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Window {
+    Resize(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Input(Input),
+    Window(Window),
+    Quit(u32),
+}
+
+fn payload(event: Event) -> u32 {
+    delegate_match! {
+        match event {
+            // Expands to `Event::Input(Input::Key(e))`, ..., `Event::Quit(e)`.
+            Event::{ Input::{ Key, Mouse }, Window::{ Resize }, Quit }(e) => e,
+        }
+    }
+}
+
+fn describe(event: Event) -> String {
+    delegate_match! {
+        match event {
+            Event::{ Input::{ Key: 1, Mouse: 2 }, Window::{ Resize: 3 } }(e) => {
+                format!(
+                    "{}::{} #{}/{} {} {e}",
+                    stringify!($entry_pat.parent),
+                    stringify!($entry_pat),
+                    $entry_index,
+                    $entry_count,
+                    $assoc_ts,
+                )
+            }
+            Event::Quit(_) => String::from("quit"),
+        }
+    }
+}
+
+fn bump(event: Event) -> Event {
+    delegate_match! {
+        match event {
+            // `$entry_path` is the path of the inner variant.
+            Event::{ Input::{ Key, Mouse }, Window::{ Resize } }(e) => Event::$entry_pat.parent($entry_path(e + 1)),
+            Event::Quit(e) => Event::Quit(e),
+        }
+    }
+}
+
+fn attributes(event: Event) -> u32 {
+    delegate_match! {
+        match event {
+            // Attributes of a nested group apply to all of its entries.
+            Event::{ #[cfg(any())] Input::{ Key, Mouse }, Window::{ Resize } }(e) => e,
+            _ => 0,
+        }
+    }
+}
+
+fn same_kind(a: Event, b: Event) -> Option<u32> {
+    delegate_match! {
+        match (a, b) {
+            // `$same` repeats the nested entry, e.g. `Event::Input(Input::Key(y))`.
+            (Event::{ Input::{ Key, Mouse }, Quit }(x), Event::$same(y)) => Some(x + y),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Top {
+    Event(Event),
+}
+
+fn deep(top: Top) -> Option<(&'static str, u32)> {
+    delegate_match! {
+        match top {
+            Top::{ Event::{ Input::{ Key, Mouse } } }(e) => Some((stringify!($entry_pat.parent), e)),
+            Top::Event(_) => None,
+        }
+    }
+}
+
+#[test]
+fn test_nested_entries() {
+    assert_eq!(payload(Event::Input(Input::Mouse(4))), 4);
+    assert_eq!(payload(Event::Window(Window::Resize(5))), 5);
+    assert_eq!(payload(Event::Quit(6)), 6);
+
+    assert_eq!(describe(Event::Input(Input::Key(7))), "Input::Key #0/3 1 7");
+    assert_eq!(
+        describe(Event::Window(Window::Resize(8))),
+        "Window::Resize #2/3 3 8"
+    );
+
+    assert_eq!(
+        bump(Event::Input(Input::Mouse(1))),
+        Event::Input(Input::Mouse(2))
+    );
+    assert_eq!(
+        bump(Event::Window(Window::Resize(1))),
+        Event::Window(Window::Resize(2))
+    );
+
+    assert_eq!(attributes(Event::Input(Input::Key(1))), 0);
+    assert_eq!(attributes(Event::Input(Input::Mouse(1))), 0);
+    assert_eq!(attributes(Event::Window(Window::Resize(1))), 1);
+
+    assert_eq!(
+        deep(Top::Event(Event::Input(Input::Mouse(9)))),
+        Some(("Input", 9))
+    );
+    assert_eq!(deep(Top::Event(Event::Quit(9))), None);
+
+    assert_eq!(
+        same_kind(Event::Input(Input::Key(1)), Event::Input(Input::Key(2))),
+        Some(3)
+    );
+    assert_eq!(same_kind(Event::Quit(1), Event::Quit(4)), Some(5));
+    assert_eq!(
+        same_kind(Event::Input(Input::Key(1)), Event::Input(Input::Mouse(2))),
+        None
+    );
+}
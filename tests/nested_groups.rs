@@ -0,0 +1,115 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Circle(u32),
+    Square(u32),
+    Triangle(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Msg {
+    Ping,
+    Pong,
+    Quit,
+}
+
+struct Wrapper {
+    shape: Shape,
+    scale: u32,
+}
+
+fn option_size(shape: Option<Shape>) -> u32 {
+    delegate_match! {
+        match shape {
+            Some(Shape::{ Circle, Square, Triangle }(size)) => size,
+            None => 0,
+        }
+    }
+}
+
+fn result_name(msg: Result<Msg, ()>) -> &'static str {
+    delegate_match! {
+        match msg {
+            Ok(Msg::{ Ping, Pong }) => stringify!($entry_ident),
+            Ok(Msg::Quit) | Err(()) => "other",
+        }
+    }
+}
+
+#[allow(clippy::match_ref_pats, reason = "tests a group behind a reference")]
+fn reference(shape: &Shape) -> u32 {
+    delegate_match! {
+        match shape {
+            // An or-pattern entry is expanded in place.
+            &Shape::{ Circle: 2, Square | Triangle: 3 }(size) => size * $assoc_ts,
+        }
+    }
+}
+
+fn binding(shape: Shape) -> (Shape, u32) {
+    delegate_match! {
+        match shape {
+            whole @ Shape::{ Circle, Square, Triangle }(size) => (whole, size),
+        }
+    }
+}
+
+fn slice_first(shapes: &[Shape]) -> Option<u32> {
+    delegate_match! {
+        match shapes {
+            [Shape::{ Circle, Square }(size), ..] => Some(*size),
+            _ => None,
+        }
+    }
+}
+
+fn struct_field(wrapper: &Wrapper) -> u32 {
+    delegate_match! {
+        match wrapper {
+            Wrapper { shape: Shape::{ Circle, Square, Triangle }(size), scale } => size * scale,
+        }
+    }
+}
+
+fn nested_pair(pair: Option<(Msg, Shape)>) -> String {
+    delegate_match! {
+        match pair {
+            // Several groups at different depths expand to every combination.
+            Some((Msg::{ Ping, Pong }, Shape::{ Circle, Square }(_))) => {
+                format!("{}-{}", stringify!($1.entry_ident), stringify!($2.entry_ident))
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+#[test]
+fn test_nested_groups() {
+    assert_eq!(option_size(Some(Shape::Square(4))), 4);
+    assert_eq!(option_size(None), 0);
+
+    assert_eq!(result_name(Ok(Msg::Ping)), "Ping");
+    assert_eq!(result_name(Ok(Msg::Pong)), "Pong");
+    assert_eq!(result_name(Ok(Msg::Quit)), "other");
+
+    assert_eq!(reference(&Shape::Circle(3)), 6);
+    assert_eq!(reference(&Shape::Triangle(3)), 9);
+
+    assert_eq!(binding(Shape::Circle(5)), (Shape::Circle(5), 5));
+
+    assert_eq!(slice_first(&[Shape::Square(2), Shape::Circle(1)]), Some(2));
+    assert_eq!(slice_first(&[Shape::Triangle(2)]), None);
+
+    let wrapper = Wrapper {
+        shape: Shape::Triangle(3),
+        scale: 2,
+    };
+    assert_eq!(struct_field(&wrapper), 6);
+
+    assert_eq!(
+        nested_pair(Some((Msg::Pong, Shape::Circle(0)))),
+        "Pong-Circle"
+    );
+    assert_eq!(nested_pair(Some((Msg::Quit, Shape::Circle(0)))), "");
+}
@@ -0,0 +1,64 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Codec {
+    Gzip,
+    Zstd,
+}
+
+fn describe(format: Format, codec: Codec) -> String {
+    delegate_match! {
+        match format {
+            Format::{ Json: "json", Yaml: "yaml" } => {
+                delegate_match! {
+                    match codec {
+                        // Placeholders belong to the innermost invocation,
+                        // `$^` reaches the enclosing one.
+                        Codec::{ Gzip: "gz", Zstd: "zst" } => {
+                            format!(
+                                "{}.{} ({} + {})",
+                                $^assoc_ts,
+                                $assoc_ts,
+                                stringify!($^entry_pat),
+                                stringify!($entry_pat),
+                            )
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn nested_strings(format: Format, codec: Codec) -> &'static str {
+    delegate_match! {
+        match format {
+            #![delegate(interpolate_strings)]
+            Format::{ Json, Yaml } => {
+                delegate_match! {
+                    match codec {
+                        #![delegate(interpolate_strings)]
+                        Codec::{ Gzip, Zstd } => "$^entry_ident/$entry_ident",
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_nested_scope() {
+    assert_eq!(describe(Format::Json, Codec::Gzip), "json.gz (Json + Gzip)");
+    assert_eq!(
+        describe(Format::Yaml, Codec::Zstd),
+        "yaml.zst (Yaml + Zstd)"
+    );
+    assert_eq!(nested_strings(Format::Json, Codec::Zstd), "Json/Zstd");
+    assert_eq!(nested_strings(Format::Yaml, Codec::Gzip), "Yaml/Gzip");
+}
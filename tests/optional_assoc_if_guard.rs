@@ -13,12 +13,12 @@ fn test(x: Data) -> i32 {
             // - `$assoc_ts` only available for I16.
             Data::{ I16: let val = val as i32, I32 }(val) if val > 0 => {
                 let val = (val * 2);
-                $assoc_ts;
+                $assoc_ts(or:);
                 val
             }
             Data::{ I16: let val = val as i32, I32 }(val) => {
                 let val = -val;
-                $assoc_ts;
+                $assoc_ts(or:);
                 val
             },
         }
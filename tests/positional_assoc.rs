@@ -0,0 +1,47 @@
+use std::any::Any;
+
+use delegate_match::delegate_match;
+
+enum Value {
+    Int(i32),
+    Flag(bool),
+    Maybe(Option<u8>),
+}
+
+fn describe(v: Value) -> (Box<dyn Any>, &'static str, usize) {
+    delegate_match! {
+        match v {
+            // `$assoc_ts.0` is a type, `$assoc_ts.1` and `$assoc_ts.2` are expressions.
+            Value::{ Int: (i32, "int", 4), Flag: (bool, "flag", 1), Maybe: (Option<u8>, "maybe", 2) }(x) => {
+                let boxed: Box<$assoc_ts.0> = Box::new(x);
+                assert_eq!(core::mem::size_of::<$assoc_ts.0>(), $assoc_ts.2);
+                (boxed as Box<dyn Any>, $assoc_ts.1, $assoc_ts.2)
+            }
+        }
+    }
+}
+
+fn tuple_field(v: Value) -> i32 {
+    delegate_match! {
+        match v {
+            // Regular tuple field access is kept for non-positional associated items.
+            Value::{ Int: TUPLE, Flag: TUPLE, Maybe: TUPLE }(_) => $assoc_ts.0,
+        }
+    }
+}
+
+const TUPLE: (i32, i32) = (7, 8);
+
+#[test]
+fn test_positional_assoc() {
+    let (boxed, name, size) = describe(Value::Int(3));
+    assert_eq!(boxed.downcast_ref::<i32>(), Some(&3));
+    assert_eq!((name, size), ("int", 4));
+    let (boxed, name, size) = describe(Value::Flag(true));
+    assert_eq!(boxed.downcast_ref::<bool>(), Some(&true));
+    assert_eq!((name, size), ("flag", 1));
+    let (boxed, name, size) = describe(Value::Maybe(Some(1)));
+    assert_eq!(boxed.downcast_ref::<Option<u8>>(), Some(&Some(1)));
+    assert_eq!((name, size), ("maybe", 2));
+    assert_eq!(tuple_field(Value::Flag(false)), 7);
+}
@@ -0,0 +1,60 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Level {
+    Low(u8),
+    Mid(u8),
+    High(u8),
+}
+
+fn siblings(level: Level) -> (&'static str, [u8; 3], &'static str) {
+    delegate_match! {
+        match level {
+            Level::{ Low: 1, Mid: 5, High: 10 }(_) => {
+                // Every arm sees all entries of the group.
+                const ALL: [u8; 3] = [$( $assoc_ts ),*];
+                let expected = concat!("expected one of ", $( stringify!($entry_pat), " " ),*);
+                (stringify!($entry_pat), ALL, expected)
+            }
+        }
+    }
+}
+
+#[allow(clippy::identity_op, reason = "placeholders expand to literals")]
+fn value(level: Level) -> u8 {
+    delegate_match! {
+        match level {
+            // Repetition in the arm pattern, with the `|` separator.
+            Level::{ Low: 1, Mid: 3 }($( $assoc_ts )|*) => 0,
+            Level::{ Low: 0, Mid: 1, High: 2 }(v) => v + $( $entry_index )+*,
+        }
+    }
+}
+
+#[allow(clippy::identity_op, reason = "placeholders expand to literals")]
+fn total(level: Level) -> u32 {
+    let mut sum = 0;
+    delegate_match! {
+        match level {
+            Level::{ Low: 1, Mid: 2, High: 3 }(_) => {
+                // No separator, and the current entry is still available outside the repetition.
+                $( sum += $assoc_ts; )*
+                sum * $assoc_ts
+            }
+        }
+    }
+}
+
+#[test]
+fn test_repetition() {
+    let (name, all, expected) = siblings(Level::Mid(0));
+    assert_eq!(name, "Mid");
+    assert_eq!(all, [1, 5, 10]);
+    assert_eq!(expected, "expected one of Low Mid High ");
+    assert_eq!(value(Level::Low(1)), 0);
+    assert_eq!(value(Level::Mid(1)), 0);
+    assert_eq!(value(Level::Low(0)), 3);
+    assert_eq!(value(Level::High(1)), 4);
+    assert_eq!(total(Level::Low(0)), 6);
+    assert_eq!(total(Level::High(0)), 18);
+}
@@ -0,0 +1,70 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl Value {
+    fn merge(&self, other: &Self) -> Option<Self> {
+        delegate_match! {
+            match (self, other) {
+                (Value::Text(a), Value::Text(b)) => Some(Value::Text(format!("{a}{b}"))),
+                // Both positions use the same entry, and other pairs fall through to `mismatch`.
+                #[delegate(mismatch = None)]
+                (Value::{ Int, Float }(a), Value::$same(b)) => Some($entry_path(a + b)),
+            }
+        }
+    }
+
+    fn common_kind(&self, other: &Self) -> Option<&'static str> {
+        delegate_match! {
+            match (self, other) {
+                #[delegate(mismatch = None)]
+                (Value::{ Int, Float, Text }(_), Value::$same(_)) => Some(stringify!($entry_ident)),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Add,
+    Mul,
+}
+
+fn apply(op: Op, a: Value, b: Value) -> Option<i64> {
+    delegate_match! {
+        match (op, a, b) {
+            // `$2.same` refers to the second group, and its path and pattern may differ.
+            #[delegate(mismatch = None)]
+            (Op::{ Add: i64::wrapping_add, Mul: i64::wrapping_mul }, Value::{ Int }(x), Value::$2.same(y)) => Some($assoc_ts(x, y)),
+        }
+    }
+}
+
+#[test]
+fn test_same_entry() {
+    assert_eq!(Value::Int(1).merge(&Value::Int(2)), Some(Value::Int(3)));
+    assert_eq!(
+        Value::Float(0.5).merge(&Value::Float(0.25)),
+        Some(Value::Float(0.75))
+    );
+    assert_eq!(
+        Value::Text("a".into()).merge(&Value::Text("b".into())),
+        Some(Value::Text("ab".into()))
+    );
+    assert_eq!(Value::Int(1).merge(&Value::Float(2.0)), None);
+
+    assert_eq!(
+        Value::Text(String::new()).common_kind(&Value::Text("x".into())),
+        Some("Text")
+    );
+    assert_eq!(Value::Int(0).common_kind(&Value::Float(0.0)), None);
+
+    assert_eq!(apply(Op::Add, Value::Int(2), Value::Int(3)), Some(5));
+    assert_eq!(apply(Op::Mul, Value::Int(2), Value::Int(3)), Some(6));
+    assert_eq!(apply(Op::Mul, Value::Int(2), Value::Float(3.0)), None);
+}
@@ -0,0 +1,86 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    Circle(f64),
+    Square(f64),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Count {
+    Few(u8),
+    Many(u16),
+}
+
+// A custom sigil avoids clashing with `macro_rules!` metavariables.
+macro_rules! shape_name {
+    ($shape:expr) => {
+        delegate_match! {
+            match $shape {
+                #![delegate(sigil = "@")]
+                Shape::{ Circle, Square }(_) => stringify!(@entry_pat),
+            }
+        }
+    };
+}
+
+fn tilde_name(shape: Shape) -> &'static str {
+    delegate_match! {
+        match shape {
+            #![delegate(sigil = "~")]
+            Shape::{ Circle: "round", Square: "square" }(_) => ~assoc_ts,
+        }
+    }
+}
+
+fn scaled(shape: Shape, by: f64) -> f64 {
+    delegate_match! {
+        match shape {
+            Shape::{ Circle: 3.0, Square: 4.0 }(size) => {
+                // `$$` is an escape for a literal `$`, e.g. for macro definitions in the body.
+                macro_rules! scale {
+                    ($$x:expr) => {
+                        $$x * $assoc_ts
+                    };
+                }
+                scale!(size * by)
+            }
+        }
+    }
+}
+
+// Other sigils are also operators, so a parenthesized group after them is only a repetition
+// when followed by `*`.
+fn remainder(shape: Shape, v: u32) -> u32 {
+    delegate_match! {
+        match shape {
+            #![delegate(sigil = "%")]
+            Shape::{ Circle: 2, Square: 3 }(_) => v % (%assoc_ts + 1),
+        }
+    }
+}
+
+fn is_small(count: Count) -> bool {
+    delegate_match! {
+        match count {
+            #![delegate(sigil = "@")]
+            Count::{ Few, Many }(n @ (1 | 2)) => n < 3,
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_sigil() {
+    assert_eq!(shape_name!(Shape::Circle(1.0)), "Circle");
+    assert_eq!(shape_name!(Shape::Square(1.0)), "Square");
+    assert_eq!(tilde_name(Shape::Circle(1.0)), "round");
+    assert_eq!(tilde_name(Shape::Square(1.0)), "square");
+    assert!((scaled(Shape::Circle(1.0), 2.0) - 6.0).abs() < f64::EPSILON);
+    assert!((scaled(Shape::Square(2.0), 2.0) - 16.0).abs() < f64::EPSILON);
+    assert_eq!(remainder(Shape::Circle(1.0), 7), 1);
+    assert_eq!(remainder(Shape::Square(1.0), 7), 3);
+    assert!(is_small(Count::Few(2)));
+    assert!(is_small(Count::Many(1)));
+    assert!(!is_small(Count::Many(3)));
+}
@@ -0,0 +1,42 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug)]
+enum MouseEvent {
+    Scroll(i16, i16),
+    Position(i32, i32),
+}
+
+fn describe(ev: &MouseEvent) -> String {
+    delegate_match! {
+        match ev {
+            #![delegate(interpolate_strings)]
+            // Placeholders inside string literals are replaced by their stringified tokens,
+            // including format strings.
+            MouseEvent::{ Scroll: wheel, Position: pointer }(x, y) => {
+                format!("$assoc_ts $entry_pat #$entry_index -> ({x}, {y}), $unknown costs $5")
+            }
+        }
+    }
+}
+
+fn verbatim(ev: &MouseEvent) -> &'static str {
+    delegate_match! {
+        match ev {
+            // Without the option, string literals are left untouched.
+            MouseEvent::{ Scroll, Position }(..) => "$entry_pat",
+        }
+    }
+}
+
+#[test]
+fn test_string_interpolation() {
+    assert_eq!(
+        describe(&MouseEvent::Scroll(1, 2)),
+        "wheel Scroll #0 -> (1, 2), $unknown costs $5"
+    );
+    assert_eq!(
+        describe(&MouseEvent::Position(3, 4)),
+        "pointer Position #1 -> (3, 4), $unknown costs $5"
+    );
+    assert_eq!(verbatim(&MouseEvent::Scroll(0, 0)), "$entry_pat");
+}
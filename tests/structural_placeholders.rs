@@ -0,0 +1,51 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, PartialEq)]
+enum MyEnum {
+    A(i32),
+    B(i32),
+}
+
+#[derive(Debug, PartialEq)]
+enum Other {
+    A(i64),
+    B(i64),
+}
+
+fn convert(e: MyEnum) -> Other {
+    delegate_match! {
+        match e {
+            // `$entry_ident` names the variant in another enum.
+            MyEnum::{ A, B }(x) => Other::$entry_ident(i64::from(x) * 2),
+        }
+    }
+}
+
+fn increment(e: MyEnum) -> MyEnum {
+    delegate_match! {
+        match e {
+            // `$entry_path` constructs the variant, regardless of how the entry pattern is written.
+            MyEnum::{ A(x), B(x) } => $entry_path(x + 1),
+        }
+    }
+}
+
+fn describe(e: &MyEnum) -> String {
+    delegate_match! {
+        match e {
+            MyEnum::{ A, B }(_x) => {
+                format!("{} {} {}", stringify!($arm_path), stringify!($entry_ident), stringify!($arm_pat))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_structural_placeholders() {
+    assert_eq!(convert(MyEnum::A(1)), Other::A(2));
+    assert_eq!(convert(MyEnum::B(2)), Other::B(4));
+    assert_eq!(increment(MyEnum::A(1)), MyEnum::A(2));
+    assert_eq!(increment(MyEnum::B(2)), MyEnum::B(3));
+    assert_eq!(describe(&MyEnum::A(0)), "MyEnum A (_x)");
+    assert_eq!(describe(&MyEnum::B(0)), "MyEnum B (_x)");
+}
@@ -0,0 +1,63 @@
+use delegate_match::delegate_match;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Src {
+    A(u8),
+    B(u8),
+    C(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dst {
+    X(u8),
+    Y(u8),
+    Z(u8),
+}
+
+fn increment(src: Src) -> Src {
+    delegate_match! {
+        match src {
+            // A single group is unaffected by zip mode.
+            #[delegate(zip)]
+            Src::{ A, B, C }(v) => $entry_path(v + 1),
+        }
+    }
+}
+
+fn matches(src: Src, dst: Dst) -> Option<u8> {
+    delegate_match! {
+        match (src, dst) {
+            // The i-th entries of both groups are paired into one arm.
+            #[delegate(zip)]
+            (Src::{ A, B, C }(x), Dst::{ X, Y, Z }(y)) => Some(x + y),
+            _ => None,
+        }
+    }
+}
+
+fn pair_name(src: Src, dst: Dst) -> Option<&'static str> {
+    delegate_match! {
+        match (src, dst) {
+            // Other attributes are kept, and the option only applies to its arm.
+            #[allow(unused_variables)]
+            #[delegate(zip)]
+            (Src::{ A, B }(x), Dst::{ Y, Z }(y)) => Some(concat!(stringify!($1.entry_ident), "->", stringify!($2.entry_ident))),
+            (Src::{ C }(_), Dst::{ X, Y, Z }(_)) => Some(concat!("C->", stringify!($2.entry_ident))),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_zip_groups() {
+    assert_eq!(increment(Src::B(2)), Src::B(3));
+
+    assert_eq!(matches(Src::A(1), Dst::X(2)), Some(3));
+    assert_eq!(matches(Src::C(1), Dst::Z(3)), Some(4));
+    assert_eq!(matches(Src::A(1), Dst::Y(2)), None);
+
+    assert_eq!(pair_name(Src::A(0), Dst::Y(0)), Some("A->Y"));
+    assert_eq!(pair_name(Src::B(0), Dst::Z(0)), Some("B->Z"));
+    assert_eq!(pair_name(Src::A(0), Dst::Z(0)), None);
+    assert_eq!(pair_name(Src::C(0), Dst::X(0)), Some("C->X"));
+}